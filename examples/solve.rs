@@ -18,15 +18,28 @@ fn main() -> Result<(), &'static str> {
     let attacker = args[4].parse::<Player>()?;
     println!("Attacker: {:?}", attacker);
 
-    let board = args[5].parse::<Board>()?;
+    let notation = match args.get(6) {
+        Some(s) => s.parse::<Notation>()?,
+        None => Notation::Alphanumeric,
+    };
+    println!("Notation: {:?}", notation);
+
+    let board = Board::from_str_with(&args[5], notation)?;
     println!("Board:\n{}\n", board.to_pretty_string());
 
-    solve_print(mode, limit, board, attacker, threat_limit);
+    solve_print(mode, limit, board, attacker, threat_limit, notation);
 
     Ok(())
 }
 
-fn solve_print(mode: SolveMode, limit: u8, board: Board, attacker: Player, threat_limit: u8) {
+fn solve_print(
+    mode: SolveMode,
+    limit: u8,
+    board: Board,
+    attacker: Player,
+    threat_limit: u8,
+    notation: Notation,
+) {
     println!("Solving...\n");
     let start = Instant::now();
     let solution = solve(mode, limit, &board, attacker, threat_limit);
@@ -36,7 +49,7 @@ fn solve_print(mode: SolveMode, limit: u8, board: Board, attacker: Player, threa
         Some(m) => {
             println!("End: {}", m.end);
             println!("Times (Length): {} ({})", m.n_times(), m.n_moves());
-            println!("Moves: {}", Points(m.path));
+            println!("Moves: {}", Points(m.path).to_string_with(notation));
         }
         None => println!("None"),
     }
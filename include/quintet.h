@@ -0,0 +1,82 @@
+#ifndef QUINTET_H
+#define QUINTET_H
+
+/* Generated by cbindgen from src/ffi.rs. Do not edit manually. */
+
+#include <stdarg.h>
+#include <stdbool.h>
+#include <stddef.h>
+#include <stdint.h>
+#include <stdlib.h>
+
+typedef enum QuintetEndKind {
+  QuintetEndKind_None = 0,
+  QuintetEndKind_Fours = 1,
+  QuintetEndKind_Forbidden = 2,
+  QuintetEndKind_Unknown = 3,
+} QuintetEndKind;
+
+typedef enum QuintetStatus {
+  QuintetStatus_Found = 0,
+  QuintetStatus_NotFound = 1,
+  QuintetStatus_Exhausted = 2,
+  QuintetStatus_Error = 3,
+} QuintetStatus;
+
+typedef struct QuintetBoard QuintetBoard;
+
+typedef struct QuintetSolution {
+  enum QuintetStatus status;
+  enum QuintetEndKind end_kind;
+  uint8_t end_points[2];
+  uint8_t n_end_points;
+  uint8_t *path;
+  size_t path_len;
+  char *error;
+} QuintetSolution;
+
+typedef struct QuintetSolveOptions {
+  uint8_t mode;
+  uint8_t limit;
+  uint8_t black;
+  uint8_t threat_limit;
+  uint64_t max_nodes;
+} QuintetSolveOptions;
+
+#ifdef __cplusplus
+extern "C" {
+#endif // __cplusplus
+
+struct QuintetBoard *quintet_board_new(void);
+
+struct QuintetBoard *quintet_board_from_stones(const uint8_t *blacks,
+                                               size_t n_blacks,
+                                               const uint8_t *whites,
+                                               size_t n_whites);
+
+struct QuintetBoard *quintet_board_from_str(const char *s, uint8_t notation);
+
+void quintet_board_free(struct QuintetBoard *board);
+
+bool quintet_board_put(struct QuintetBoard *board, bool black, uint8_t code);
+
+bool quintet_board_remove(struct QuintetBoard *board, uint8_t code);
+
+uint8_t quintet_board_forbidden(const struct QuintetBoard *board, uint8_t code);
+
+size_t quintet_board_forbiddens(const struct QuintetBoard *board, uint8_t *out, size_t cap);
+
+char *quintet_board_to_string(const struct QuintetBoard *board);
+
+void quintet_string_free(char *s);
+
+struct QuintetSolution *quintet_solve(const struct QuintetBoard *board,
+                                      const struct QuintetSolveOptions *options);
+
+void quintet_solution_free(struct QuintetSolution *solution);
+
+#ifdef __cplusplus
+} // extern "C"
+#endif // __cplusplus
+
+#endif /* QUINTET_H */
@@ -1 +1,18 @@
+#[cfg(feature = "analysis")]
+mod analyzer;
+#[cfg(feature = "analysis")]
+mod annotation;
 pub mod field;
+#[cfg(feature = "analysis")]
+mod motif;
+#[cfg(feature = "analysis")]
+mod status;
+
+#[cfg(feature = "analysis")]
+pub use analyzer::Analyzer;
+#[cfg(feature = "analysis")]
+pub use annotation::{annotate, Annotation};
+#[cfg(feature = "analysis")]
+pub use motif::{motifs, Motif, MotifKind};
+#[cfg(feature = "analysis")]
+pub use status::{status, status_in, Status, StatusOptions};
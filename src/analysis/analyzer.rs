@@ -0,0 +1,110 @@
+use super::status::*;
+use crate::board::*;
+use crate::mate::*;
+
+// Long-lived analysis of a game, which keeps solver state of both players while stones are put and removed.
+// Analyzing every position of a game costs far less than independent calls of status.
+// Tables of both players are kept within table_bytes, as they would otherwise grow with every analyzed position.
+pub struct Analyzer {
+    board: Board,
+    moves: Vec<Point>,
+    options: StatusOptions,
+    black: Session,
+    white: Session,
+}
+
+impl Analyzer {
+    pub fn init(board: &Board, options: StatusOptions, table_bytes: usize) -> Self {
+        Self {
+            board: board.clone(),
+            moves: vec![],
+            options: options,
+            black: Session::bounded(Player::Black, options.threat_limit, table_bytes / 2),
+            white: Session::bounded(Player::White, options.threat_limit, table_bytes / 2),
+        }
+    }
+
+    pub fn board(&self) -> &Board {
+        &self.board
+    }
+
+    pub fn play(&mut self, r: Player, p: Point) -> Result<(), &'static str> {
+        if self.board.stone(p).is_some() {
+            return Err("Point already occupied");
+        }
+        self.board.put_mut(r, p);
+        self.moves.push(p);
+        self.prune();
+        Ok(())
+    }
+
+    pub fn undo(&mut self) -> Option<Point> {
+        let p = self.moves.pop()?;
+        self.board.remove_mut(p);
+        self.prune();
+        Some(p)
+    }
+
+    pub fn status(&mut self, to_move: Player) -> Status {
+        let (board, options) = (&self.board, &self.options);
+        status_in(board, to_move, options, &mut self.black, &mut self.white)
+    }
+
+    pub fn stats(&self) -> Stats {
+        self.black.stats().merge(self.white.stats())
+    }
+
+    fn prune(&mut self) {
+        self.black.prune();
+        self.white.prune();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::Player::*;
+
+    #[test]
+    fn test_analyzer() -> Result<(), String> {
+        // No. 02 from 5-moves-to-end problems by Hiroshi Okabe
+        let board = "H10,H9,J9,H8,I7/I11,I9,G8,J8,H7";
+        let board = Board::from_str_with(board, Notation::Alphanumeric)?;
+        let options = StatusOptions {
+            vcf_limit: u8::MAX,
+            vct_limit: 4,
+            vct_mode: SolveMode::VCTDFPNS,
+            threat_limit: 1,
+        };
+        let mut analyzer = Analyzer::init(&board, options, 1 << 24);
+
+        let result = analyzer.status(Black);
+        assert_eq!(result, status(&board, Black, &options));
+        let first = analyzer.black.stats().inserts;
+
+        let moves = "F10,G9".parse::<Points>()?;
+        analyzer.play(Black, moves.0[0])?;
+        analyzer.play(White, moves.0[1])?;
+        assert!(analyzer.play(Black, moves.0[1]).is_err());
+        let board = board.put(Black, moves.0[0]).put(White, moves.0[1]);
+        assert_eq!(analyzer.board().zobrist_hash(), board.zobrist_hash());
+
+        let result = analyzer.status(Black);
+        assert_eq!(result, status(&board, Black, &options));
+        let vct = result.vct.map(|m| Points(m.path).to_string());
+        assert_eq!(vct.as_deref(), Some("I10,G10,H11,H12,G12"));
+        // Black's proof is reused, while White's threats are searched again
+        let second = analyzer.black.stats().inserts - first;
+        let mut fresh = Analyzer::init(&board, options, 1 << 24);
+        fresh.status(Black);
+        assert!(second * 10 < fresh.black.stats().inserts);
+        assert_eq!(analyzer.stats().capacity, fresh.stats().capacity);
+        assert!(analyzer.stats().capacity.is_some());
+
+        assert_eq!(analyzer.undo(), Some(moves.0[1]));
+        assert_eq!(analyzer.undo(), Some(moves.0[0]));
+        assert_eq!(analyzer.undo(), None);
+
+        Ok(())
+    }
+}
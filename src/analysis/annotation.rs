@@ -0,0 +1,158 @@
+use super::status::*;
+use crate::board::Player::*;
+use crate::board::StructureKind::Five;
+use crate::board::*;
+use crate::mate::*;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Annotation {
+    pub index: usize,
+    pub player: Player,
+    pub point: Point,
+    pub winning: Option<Mate>,
+    pub missed: bool,
+    pub allowed: Option<Mate>,
+    pub forbidden: Option<ForbiddenKind>,
+    pub decided: Option<Player>,
+}
+
+pub fn annotate(moves: &Points, options: &StatusOptions) -> Vec<Annotation> {
+    let mut boards = vec![Board::new()];
+    // turns[i] is the side to move before the i-th move
+    let mut turns = vec![];
+    let mut turn = Black;
+    for (i, &p) in moves.0.iter().enumerate() {
+        let next = boards[i].put(turn, p);
+        boards.push(next);
+        turns.push(turn);
+        turn = turn.opponent();
+    }
+    turns.push(turn);
+    let mut sessions = [
+        Session::new(Black, options.threat_limit),
+        Session::new(White, options.threat_limit),
+    ];
+    // wins[i] is the mate of the side to move before the i-th move
+    let wins: Vec<_> = boards
+        .iter()
+        .enumerate()
+        .map(|(i, board)| winning(board, options, &mut sessions[index(turns[i])]))
+        .collect();
+
+    let mut result = vec![];
+    for (i, &p) in moves.0.iter().enumerate() {
+        let r = turns[i];
+        let board = &boards[i];
+        let winning = wins[i].clone();
+        // The move keeps the win if the mover still wins against every reply to it
+        let missed = match &winning {
+            Some(m) if boards[i + 1].structures(r, Five).next().is_none() => {
+                let limit = m.n_times().max(options.vct_limit).saturating_sub(1);
+                sessions[index(r)]
+                    .try_solve_defended(limit, &boards[i + 1])
+                    .unwrap_or(None)
+                    .is_none()
+            }
+            _ => false,
+        };
+        // The opponent wins against every move, so that no move is to blame
+        let lost = (winning.is_none() || wins[i + 1].is_some())
+            && sessions[index(r.opponent())]
+                .try_solve_defended(options.vct_limit, board)
+                .unwrap_or(None)
+                .is_some();
+        let allowed = if lost { None } else { wins[i + 1].clone() };
+        let forbidden = if r.is_black() {
+            board.forbidden_strict(p)
+        } else {
+            None
+        };
+        let decided = if winning.is_some() {
+            Some(r)
+        } else if lost {
+            Some(r.opponent())
+        } else {
+            None
+        };
+        result.push(Annotation {
+            index: i,
+            player: r,
+            point: p,
+            winning: winning,
+            missed: missed,
+            allowed: allowed,
+            forbidden: forbidden,
+            decided: decided,
+        });
+    }
+    result
+}
+
+fn index(r: Player) -> usize {
+    if r.is_black() {
+        0
+    } else {
+        1
+    }
+}
+
+fn winning(board: &Board, options: &StatusOptions, session: &mut Session) -> Option<Mate> {
+    let (vcf, vct) = solve_both(board, options, session);
+    vcf.or(vct)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_annotate() -> Result<(), String> {
+        let moves = "H8,A1,H9,O1,H10,A15,A8,H11,H7".parse::<Points>()?;
+        let options = StatusOptions {
+            vcf_limit: u8::MAX,
+            vct_limit: 2,
+            vct_mode: SolveMode::VCTDFPNS,
+            threat_limit: 1,
+        };
+        let result = annotate(&moves, &options);
+        assert_eq!(result.len(), 9);
+
+        // White ignores the open three
+        assert!(result[5].winning.is_none());
+        assert!(result[5].allowed.is_some());
+        assert_eq!(result[5].decided, None);
+
+        // Black misses the open four
+        assert!(result[6].winning.is_some());
+        assert!(result[6].missed);
+        assert_eq!(result[6].decided, Some(Black));
+
+        // White blocks it
+        assert!(result[7].allowed.is_none());
+        assert_eq!(result[7].decided, None);
+
+        assert!(result.iter().all(|a| a.forbidden.is_none()));
+
+        let moves = "H8,A1,H9,O1,H10,A15,H11,A14".parse::<Points>()?;
+        let result = annotate(&moves, &options);
+
+        // Black makes the open four
+        assert!(result[6].winning.is_some());
+        assert!(!result[6].missed);
+
+        // White cannot stop it, so that the move is not to blame
+        assert!(result[7].winning.is_none());
+        assert!(result[7].allowed.is_none());
+        assert_eq!(result[7].decided, Some(Black));
+
+        let moves = "H8,B2,H9,C2,H10,D2,A15".parse::<Points>()?;
+        let result = annotate(&moves, &options);
+
+        // Black misses the open three and lets white make an open four
+        assert!(result[6].winning.is_some());
+        assert!(result[6].missed);
+        assert!(result[6].allowed.is_some());
+
+        Ok(())
+    }
+}
@@ -77,6 +77,13 @@ impl PotentialField {
             .collect()
     }
 
+    // Potentials of all points ordered by point code
+    pub fn values(&self) -> Vec<u8> {
+        (0..RANGE)
+            .flat_map(|x| (0..RANGE).map(move |y| self.sum(Point(x, y))))
+            .collect()
+    }
+
     #[allow(dead_code)]
     pub fn overlay(&self, board: &Board) -> String {
         (0..RANGE)
@@ -179,6 +186,11 @@ mod tests {
         );
         assert_eq!(result, expected);
 
+        let values = field.values();
+        assert_eq!(values.len(), 225);
+        assert_eq!(values[u8::from(Point(6, 10)) as usize], 9);
+        assert_eq!(values[u8::from(Point(6, 9)) as usize], 0);
+
         let field = PotentialField::init(Black, 2, &board);
         let result = field.overlay(&board);
         let expected = trim_lines_string(
@@ -0,0 +1,165 @@
+use crate::board::StructureKind::{Sword, Two};
+use crate::board::ThreatKind::*;
+use crate::board::*;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum MotifKind {
+    // Only of white, since black double-fours are forbidden and show up
+    // as ForbiddenTrap of white when white fours into them
+    FourFour,
+    FourThree,
+    OpenFour,
+    ThreeThree,
+    ForbiddenTrap,
+}
+
+pub use MotifKind::*;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Motif {
+    pub kind: MotifKind,
+    pub point: Point,
+    pub threats: Vec<Threat>,
+}
+
+pub fn motifs(board: &Board, player: Player) -> Vec<Motif> {
+    let mut result = vec![];
+    for p in candidates(board, player) {
+        if player.is_black() && board.forbidden(p).is_some() {
+            continue;
+        }
+        let next = board.put(player, p);
+        let threats = next.threats_on(p, player);
+        let fours: Vec<_> = threats.iter().filter(|t| t.kind.is_four()).collect();
+        // threes on the same line as a four are only parts of it
+        let threes: Vec<_> = threats
+            .iter()
+            .filter(|t| t.kind.is_three() && t.real)
+            .filter(|t| fours.iter().all(|f| f.direction != t.direction))
+            .collect();
+
+        let mut motif = |kind: MotifKind, involved: Vec<&Threat>| {
+            result.push(Motif {
+                kind: kind,
+                point: p,
+                threats: involved.into_iter().cloned().collect(),
+            });
+        };
+
+        if fours.iter().any(|t| t.kind == StraightFour) {
+            let involved = fours.iter().filter(|t| t.kind == StraightFour);
+            motif(OpenFour, involved.cloned().collect());
+        }
+        if player.is_white() && fours.len() >= 2 {
+            motif(FourFour, fours.clone());
+        }
+        if !fours.is_empty() && !threes.is_empty() {
+            motif(
+                FourThree,
+                fours.iter().chain(threes.iter()).cloned().collect(),
+            );
+        }
+        if player.is_white() && threes.len() >= 2 {
+            motif(ThreeThree, threes.clone());
+        }
+        if player.is_white() && fours.len() == 1 && fours[0].eyes.len() == 1 {
+            let eye = fours[0].eyes[0];
+            if next.forbidden(eye).is_some() {
+                motif(ForbiddenTrap, fours.clone());
+            }
+        }
+    }
+    result
+}
+
+fn candidates(board: &Board, player: Player) -> Vec<Point> {
+    let mut result: Vec<_> = board
+        .structures(player, Sword)
+        .chain(board.structures(player, Two))
+        .flat_map(|s| s.eyes())
+        .collect();
+    result.sort_by_key(|&p| u8::from(p));
+    result.dedup();
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::Player::*;
+
+    #[test]
+    fn test_motifs_black() -> Result<(), String> {
+        let board = "
+         . . . . . . . . . . . . . . .
+         . . . . . . . . . . . . . . .
+         . . . . . . . . . . . . . . .
+         . . . . . . . . . . . . . . .
+         . . . . . . . . . . . . . . .
+         . . . . . . . . . . . . . . .
+         . . . . . . . . . . . . . . .
+         . . . . . . . o . . . . . . .
+         . . . . . . . o . . . . . . .
+         . . . . . . . o . . . . . . .
+         . . . . . . . . . . . . . . .
+         . . . . . . . . o o . . . . .
+         . . . . . . . . . . . . . . .
+         . . . . . . . . . . . . . . .
+         . . . . . . . . . . . . . . .
+        "
+        .parse::<Board>()?;
+
+        let result = motifs(&board, Black);
+        let kinds: Vec<_> = result.iter().map(|m| (m.kind, m.point)).collect();
+        let expected = [
+            (FourThree, Point(7, 3)),
+            (OpenFour, Point(7, 4)),
+            (OpenFour, Point(7, 8)),
+        ];
+        assert_eq!(kinds, expected);
+
+        let involved: Vec<_> = result[0].threats.iter().map(|t| t.kind).collect();
+        assert_eq!(involved, [SimpleFour, SolidThree]);
+
+        // H8 is a forbidden double-four of black
+        let board = Board::from_str_with("E8,F8,G8,H5,H6,H7/", Notation::Alphanumeric)?;
+        let result = motifs(&board, Black);
+        assert!(result.iter().all(|m| m.kind != FourFour));
+        assert!(result.iter().all(|m| m.point != Point(7, 7)));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_motifs_white() -> Result<(), String> {
+        let board = "
+         . . . . . . . . . . . . . . .
+         . . . . . . . . . . . . . . .
+         . . . . . . . . . . . . . . .
+         . . . . . . . . . . . . . . .
+         . . . . . . . . . . . . . . .
+         . . . . . . . o . . . . . . .
+         . . . . . . . o . . . . . . .
+         . . . . . o o . . . . . . . .
+         . . . . . . x . . . . . . . .
+         . . . . . x . . . . . . . . .
+         . . . . x . . . . . . . . . .
+         . . . . . . . . . . . . . . .
+         . . o . . . . . . . . . . . .
+         . . . . . . . . . . . . . . .
+         . . . . . . . . . . . . . . .
+        "
+        .parse::<Board>()?;
+
+        let result = motifs(&board, White);
+        let kinds: Vec<_> = result.iter().map(|m| (m.kind, m.point)).collect();
+        let expected = [
+            (ForbiddenTrap, Point(3, 3)),
+            (OpenFour, Point(7, 7)),
+            (ForbiddenTrap, Point(8, 8)),
+        ];
+        assert_eq!(kinds, expected);
+
+        Ok(())
+    }
+}
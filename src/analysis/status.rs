@@ -0,0 +1,183 @@
+use crate::board::StructureKind::Sword;
+use crate::board::*;
+use crate::mate::*;
+
+#[derive(Debug, Clone, Copy)]
+pub struct StatusOptions {
+    pub vcf_limit: u8,
+    pub vct_limit: u8,
+    pub vct_mode: SolveMode,
+    pub threat_limit: u8,
+}
+
+impl Default for StatusOptions {
+    fn default() -> Self {
+        Self {
+            vcf_limit: u8::MAX,
+            vct_limit: 5,
+            vct_mode: SolveMode::VCTDFPNS,
+            threat_limit: 3,
+        }
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Status {
+    pub to_move: Player,
+    pub vcf: Option<Mate>,
+    pub vct: Option<Mate>,
+    pub threat_vcf: Option<Mate>,
+    pub threat_vct: Option<Mate>,
+    pub forbiddens: Vec<(ForbiddenKind, Point)>,
+    pub fours: Vec<Point>,
+    pub threat_fours: Vec<Point>,
+}
+
+impl Status {
+    pub fn winning(&self) -> Option<&Mate> {
+        self.vcf.as_ref().or(self.vct.as_ref())
+    }
+
+    pub fn threat(&self) -> Option<&Mate> {
+        self.threat_vcf.as_ref().or(self.threat_vct.as_ref())
+    }
+}
+
+pub fn status(board: &Board, to_move: Player, options: &StatusOptions) -> Status {
+    let mut black = Session::new(Player::Black, options.threat_limit);
+    let mut white = Session::new(Player::White, options.threat_limit);
+    status_in(board, to_move, options, &mut black, &mut white)
+}
+
+// Status of which mates are searched in the sessions of both players, sharing their tables
+pub fn status_in(
+    board: &Board,
+    to_move: Player,
+    options: &StatusOptions,
+    black: &mut Session,
+    white: &mut Session,
+) -> Status {
+    let (mover, opponent) = if to_move.is_black() {
+        (black, white)
+    } else {
+        (white, black)
+    };
+    let forbiddens = board.forbiddens();
+    let (vcf, vct) = solve_both_in(board, options, mover, false);
+    // The threat is the opponent's mate after the player to move passes
+    let (threat_vcf, threat_vct) = solve_both_in(board, options, opponent, true);
+    let fours = four_moves(board, to_move, &forbiddens);
+    let threat_fours = four_moves(board, to_move.opponent(), &forbiddens);
+    Status {
+        to_move: to_move,
+        vcf: vcf,
+        vct: vct,
+        threat_vcf: threat_vcf,
+        threat_vct: threat_vct,
+        forbiddens: forbiddens,
+        fours: fours,
+        threat_fours: threat_fours,
+    }
+}
+
+pub(crate) fn solve_both(
+    board: &Board,
+    options: &StatusOptions,
+    session: &mut Session,
+) -> (Option<Mate>, Option<Mate>) {
+    solve_both_in(board, options, session, false)
+}
+
+fn solve_both_in(
+    board: &Board,
+    options: &StatusOptions,
+    session: &mut Session,
+    passed: bool,
+) -> (Option<Mate>, Option<Mate>) {
+    let mut f = |mode, limit| {
+        // Modes without a session are solved alone, where a pass does not change the board
+        if !Session::supports(mode) {
+            return solve(mode, limit, board, session.attacker, options.threat_limit);
+        }
+        let result = if passed {
+            session.try_solve_threat(mode, limit, board)
+        } else {
+            session.try_solve(mode, limit, board)
+        };
+        result.unwrap_or(None)
+    };
+    let vcf = f(SolveMode::VCFDFS, options.vcf_limit);
+    // A VCF short enough is also a VCT
+    let vct = match &vcf {
+        Some(m) if m.n_times() <= options.vct_limit => Some(m.clone()),
+        _ => f(options.vct_mode, options.vct_limit),
+    };
+    (vcf, vct)
+}
+
+fn four_moves(board: &Board, r: Player, forbiddens: &[(ForbiddenKind, Point)]) -> Vec<Point> {
+    let mut result: Vec<_> = board
+        .structures(r, Sword)
+        .flat_map(|s| s.eyes())
+        .filter(|p| r.is_white() || !forbiddens.iter().any(|(_, f)| f == p))
+        .collect();
+    result.sort_by_key(|&p| u8::from(p));
+    result.dedup();
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::Player::*;
+
+    #[test]
+    fn test_status() -> Result<(), String> {
+        // No. 02 from 5-moves-to-end problems by Hiroshi Okabe
+        let board = "
+         . . . . . . . . . . . . . . .
+         . . . . . . . . . . . . . . .
+         . . . . . . . . . . . . . . .
+         . . . . . . . . . . . . . . .
+         . . . . . . . . x . . . . . .
+         . . . . . . . o . . . . . . .
+         . . . . . . . o x o . . . . .
+         . . . . . . x o . x . . . . .
+         . . . . . . . x o . . . . . .
+         . . . . . . . . . . . . . . .
+         . . . . . . . . . . . . . . .
+         . . . . . . . . . . . . . . .
+         . . . . . . . . . . . . . . .
+         . . . . . . . . . . . . . . .
+         . . . . . . . . . . . . . . .
+        "
+        .parse::<Board>()?;
+
+        let options = StatusOptions {
+            vcf_limit: u8::MAX,
+            vct_limit: 4,
+            vct_mode: SolveMode::VCTDFPNS,
+            threat_limit: 1,
+        };
+        let result = status(&board, Black, &options);
+
+        assert!(result.vcf.is_none());
+        let vct = result
+            .vct
+            .as_ref()
+            .map(|m| Points(m.path.clone()).to_string());
+        assert_eq!(vct, Some("F10,G9,I10,G10,H11,H12,G12".to_string()));
+        assert_eq!(result.winning(), result.vct.as_ref());
+        assert!(result.threat_vcf.is_none());
+        assert!(result.forbiddens.is_empty());
+        assert_eq!(result.fours, [Point(7, 10), Point(7, 11)]);
+        assert!(result.threat_fours.is_empty());
+
+        // Fours of black are threats when white is to move
+        let result = status(&board, White, &options);
+        assert_eq!(result.threat_fours, [Point(7, 10), Point(7, 11)]);
+        assert!(result.fours.is_empty());
+
+        Ok(())
+    }
+}
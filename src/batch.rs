@@ -0,0 +1,294 @@
+use crate::board::*;
+use crate::mate::*;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Mutex;
+use std::thread;
+use std::time::Instant;
+
+pub struct Problem {
+    pub id: String,
+    pub board: Board,
+    pub mode: SolveMode,
+    pub limit: u8,
+    pub attacker: Player,
+    pub threat_limit: u8,
+    // Some("") means the problem is expected to have no solution
+    pub expected: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Outcome {
+    pub id: String,
+    pub found: bool,
+    pub end: Option<End>,
+    pub path: Option<String>,
+    pub n_times: u8,
+    pub n_moves: u8,
+    pub elapsed_ms: f64,
+    // Tables and VCF deadends of modes which keep them in tables
+    pub stats: Option<Stats>,
+    pub matched: Option<bool>,
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize)]
+pub struct Summary {
+    pub total: usize,
+    pub found: usize,
+    pub matched: usize,
+    pub mismatched: usize,
+    pub errors: usize,
+    pub elapsed_ms: f64,
+}
+
+#[derive(Deserialize)]
+struct Record {
+    id: Option<String>,
+    board: String,
+    mode: String,
+    limit: u8,
+    attacker: String,
+    threat_limit: Option<u8>,
+    expected: Option<String>,
+}
+
+// Reads JSON lines if the first record starts with '{', otherwise CSV with a header line
+pub fn parse(s: &str) -> Result<Vec<Problem>, String> {
+    if s.trim_start().starts_with('{') {
+        parse_jsonl(s)
+    } else {
+        parse_csv(s)
+    }
+}
+
+pub fn parse_jsonl(s: &str) -> Result<Vec<Problem>, String> {
+    lines(s)
+        .map(|(i, line)| {
+            let record: Record = serde_json::from_str(line).map_err(|e| at(i, e))?;
+            problem(i, record).map_err(|e| at(i, e))
+        })
+        .collect()
+}
+
+pub fn parse_csv(s: &str) -> Result<Vec<Problem>, String> {
+    let mut rows = lines(s);
+    let header = match rows.next() {
+        Some((_, line)) => split_csv(line),
+        None => return Ok(vec![]),
+    };
+    rows.map(|(i, line)| {
+        let fields: HashMap<_, _> = header.iter().cloned().zip(split_csv(line)).collect();
+        let field = |name: &str| fields.get(name).filter(|v| !v.is_empty()).cloned();
+        let number = |name: &str| -> Result<Option<u8>, String> {
+            field(name)
+                .map(|v| v.parse::<u8>().map_err(|e| e.to_string()))
+                .transpose()
+        };
+        let record = Record {
+            id: field("id"),
+            board: field("board").ok_or_else(|| at(i, "Missing board"))?,
+            mode: field("mode").ok_or_else(|| at(i, "Missing mode"))?,
+            limit: number("limit")
+                .map_err(|e| at(i, e))?
+                .ok_or_else(|| at(i, "Missing limit"))?,
+            attacker: field("attacker").ok_or_else(|| at(i, "Missing attacker"))?,
+            threat_limit: number("threat_limit").map_err(|e| at(i, e))?,
+            // An empty column still means "expected no solution" if the column exists
+            expected: fields.get("expected").cloned(),
+        };
+        problem(i, record).map_err(|e| at(i, e))
+    })
+    .collect()
+}
+
+pub fn run(problems: &[Problem], n_threads: usize) -> Vec<Outcome> {
+    let next = AtomicUsize::new(0);
+    let outcomes = Mutex::new(vec![None; problems.len()]);
+    thread::scope(|s| {
+        for _ in 0..n_threads.max(1) {
+            s.spawn(|| loop {
+                let i = next.fetch_add(1, Ordering::Relaxed);
+                if i >= problems.len() {
+                    break;
+                }
+                let outcome = solve_problem(&problems[i]);
+                outcomes.lock().unwrap()[i] = Some(outcome);
+            });
+        }
+    });
+    outcomes
+        .into_inner()
+        .unwrap()
+        .into_iter()
+        .flatten()
+        .collect()
+}
+
+pub fn solve_problem(problem: &Problem) -> Outcome {
+    let start = Instant::now();
+    let result = try_solve_stats(
+        problem.mode,
+        problem.limit,
+        &problem.board,
+        problem.attacker,
+        problem.threat_limit,
+    );
+    let elapsed_ms = start.elapsed().as_secs_f64() * 1000.0;
+    let (mate, stats, error) = match result {
+        Ok((mate, stats)) => (mate, stats, None),
+        Err(e) => (None, None, Some(e.to_string())),
+    };
+    let path = mate.as_ref().map(|m| Points(m.path.clone()).to_string());
+    let matched = match (&problem.expected, &error) {
+        (Some(expected), None) => Some(path.as_deref().unwrap_or("") == expected.as_str()),
+        _ => None,
+    };
+    Outcome {
+        id: problem.id.clone(),
+        found: mate.is_some(),
+        end: mate.as_ref().map(|m| m.end.clone()),
+        path: path,
+        n_times: mate.as_ref().map_or(0, |m| m.n_times()),
+        n_moves: mate.as_ref().map_or(0, |m| m.n_moves()),
+        elapsed_ms: elapsed_ms,
+        stats: stats,
+        matched: matched,
+        error: error,
+    }
+}
+
+pub fn summarize(outcomes: &[Outcome]) -> Summary {
+    let mut result = Summary::default();
+    for o in outcomes {
+        result.total += 1;
+        result.found += o.found as usize;
+        result.matched += (o.matched == Some(true)) as usize;
+        result.mismatched += (o.matched == Some(false)) as usize;
+        result.errors += o.error.is_some() as usize;
+        result.elapsed_ms += o.elapsed_ms;
+    }
+    result
+}
+
+fn problem(i: usize, record: Record) -> Result<Problem, String> {
+    let attacker = record
+        .attacker
+        .parse::<Player>()
+        .map_err(|_| format!("Invalid attacker: {}", record.attacker))?;
+    let expected = match record.expected {
+        Some(s) if !s.trim().is_empty() => Some(s.parse::<Points>()?.to_string()),
+        Some(_) => Some(String::new()),
+        None => None,
+    };
+    Ok(Problem {
+        id: record.id.unwrap_or_else(|| (i + 1).to_string()),
+        board: Board::from_str_with(&record.board, Notation::Alphanumeric)?,
+        mode: record.mode.parse()?,
+        limit: record.limit,
+        attacker: attacker,
+        threat_limit: record.threat_limit.unwrap_or(DEFAULT_THREAT_LIMIT),
+        expected: expected,
+    })
+}
+
+fn lines(s: &str) -> impl Iterator<Item = (usize, &str)> {
+    s.lines()
+        .enumerate()
+        .map(|(i, line)| (i, line.trim()))
+        .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'))
+}
+
+fn split_csv(line: &str) -> Vec<String> {
+    let mut result = vec![];
+    let mut field = String::new();
+    let mut quoted = false;
+    let mut chars = line.chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '"' if quoted && chars.peek() == Some(&'"') => {
+                field.push('"');
+                chars.next();
+            }
+            '"' => quoted = !quoted,
+            ',' if !quoted => result.push(std::mem::take(&mut field).trim().to_string()),
+            c => field.push(c),
+        }
+    }
+    result.push(field.trim().to_string());
+    result
+}
+
+fn at(i: usize, e: impl ToString) -> String {
+    format!("line {}: {}", i + 1, e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse() -> Result<(), String> {
+        let jsonl = r#"
+            {"id": "okabe02", "board": "H10,H9,J9,H8,I7/I11,I9,G8,J8,H7", "mode": "vct_dfpns", "limit": 4, "attacker": "black", "threat_limit": 1, "expected": "F10,G9,I10,G10,H11,H12,G12"}
+            {"board": "H10,H9,J9,H8,I7/I11,I9,G8,J8,H7", "mode": "vcf", "limit": 5, "attacker": "o", "expected": ""}
+        "#;
+        let problems = parse(jsonl)?;
+        assert_eq!(problems.len(), 2);
+        assert_eq!(problems[0].id, "okabe02");
+        assert_eq!(problems[1].id, "3");
+        assert_eq!(problems[1].expected.as_deref(), Some(""));
+
+        let csv = r#"
+            id,mode,limit,attacker,threat_limit,board,expected
+            okabe02,vct_dfpns,4,o,1,"H10,H9,J9,H8,I7/I11,I9,G8,J8,H7","F10,G9,I10,G10,H11,H12,G12"
+            no-vcf,vcf,5,o,,"H10,H9,J9,H8,I7/I11,I9,G8,J8,H7",
+        "#;
+        let problems = parse(csv)?;
+        assert_eq!(problems.len(), 2);
+        assert_eq!(problems[0].mode, SolveMode::VCTDFPNS);
+        assert_eq!(problems[0].board.stones(Player::Black).count(), 5);
+        assert_eq!(problems[1].expected.as_deref(), Some(""));
+        assert_eq!(problems[1].threat_limit, DEFAULT_THREAT_LIMIT);
+
+        let result = parse(r#"{"board": "H8", "mode": "vcf", "limit": 1, "attacker": "z"}"#);
+        assert_eq!(
+            result.err(),
+            Some("line 1: Invalid attacker: z".to_string())
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_run() -> Result<(), String> {
+        let csv = "
+            id,mode,limit,attacker,threat_limit,board,expected
+            vct,vct_dfpns,4,o,1,\"H10,H9,J9,H8,I7/I11,I9,G8,J8,H7\",\"F10,G9,I10,G10,H11,H12,G12\"
+            vcf,vcf,5,o,,\"H10,H9,J9,H8,I7/I11,I9,G8,J8,H7\",
+            wrong,vct_dfpns,4,o,1,\"H10,H9,J9,H8,I7/I11,I9,G8,J8,H7\",F10
+            five,vcf,1,x,,\"H8,H9,H10,H11,H12/\",
+        ";
+        let problems = parse(csv)?;
+        let outcomes = run(&problems, 2);
+        let ids: Vec<_> = outcomes.iter().map(|o| o.id.as_str()).collect();
+        assert_eq!(ids, ["vct", "vcf", "wrong", "five"]);
+
+        assert_eq!(outcomes[0].matched, Some(true));
+        assert_eq!(outcomes[0].n_times, 4);
+        assert!(outcomes[0].stats.map_or(false, |s| s.inserts > 0));
+        assert_eq!(outcomes[1].matched, Some(true));
+        assert!(!outcomes[1].found);
+        assert_eq!(outcomes[2].matched, Some(false));
+        assert_eq!(outcomes[3].matched, None);
+        assert!(outcomes[3].error.is_some());
+        assert_eq!(outcomes[3].stats, None);
+
+        let summary = summarize(&outcomes);
+        assert_eq!((summary.total, summary.found, summary.matched), (4, 2, 2));
+        assert_eq!((summary.mismatched, summary.errors), (1, 1));
+
+        Ok(())
+    }
+}
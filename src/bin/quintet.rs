@@ -0,0 +1,657 @@
+use quintet::analysis::{status, StatusOptions};
+use quintet::batch;
+use quintet::board::StructureKind::Four;
+use quintet::board::*;
+use quintet::db::Database;
+use quintet::mate::*;
+use serde_json::json;
+use std::env;
+use std::fs;
+use std::io::{self, Read};
+use std::path::Path;
+use std::process;
+use std::time::Instant;
+
+const USAGE: &str = "Usage: quintet <COMMAND> [OPTIONS] [BOARD]
+
+Commands:
+  solve        Search VCF or VCT of the attacker
+  forbiddens   List forbidden points of black
+  threats      List threes and fours
+  status       Report mates and threats of both sides
+  verify       Check that --path is a mate of the attacker
+  render       Print the board
+  batch        Solve every problem of a JSON lines or CSV file
+
+BOARD is a diagram, stones (\"H8,J9/I9\") or moves (\"H8,I9,J10\").
+It is read from --file or stdin if omitted.
+
+Options:
+  -m, --mode <MODE>           vcf, vct, vct_tss, vct_lambda, vct_pns, vct_dfpns, vct_pdfpns, vct_dfwpns, vct_dfpnp, vct_lazy [default: vcf]
+  -l, --limit <N>             Max attacks [default: 255 for vcf, 5 for vct]
+  -t, --threat-limit <N>      Max attacks of threats in VCT [default: 3]
+      --order <N>             Order of threats in vct_lambda [default: 3]
+  -p, --player <PLAYER>       Attacker or player to move: o, x, black or white [default: inferred from stones]
+  -n, --notation <NOTATION>   Point notation of input and output [default: alphanumeric]
+  -f, --file <PATH>           Read the board from a file (\"-\" for stdin)
+      --path <MOVES>          Moves to verify
+      --snapshot <PATH>       Reuse and update solver tables of solve in a file (vcf, vct_dfpns)
+      --db <PATH>             Look up and record results of solve in a solved-position file
+      --threads <N>           Worker threads of batch [default: available cores]
+      --json                  Print results as JSON
+  -h, --help                  Print this message";
+
+const COMMANDS: [&str; 7] = [
+    "solve",
+    "forbiddens",
+    "threats",
+    "status",
+    "verify",
+    "render",
+    "batch",
+];
+
+struct Options {
+    command: String,
+    mode: Option<SolveMode>,
+    limit: Option<u8>,
+    threat_limit: u8,
+    order: Option<u8>,
+    player: Option<Player>,
+    notation: Notation,
+    file: Option<String>,
+    snapshot: Option<String>,
+    db: Option<String>,
+    path: Option<String>,
+    input: Option<String>,
+    threads: Option<usize>,
+    json: bool,
+}
+
+fn main() {
+    let args = env::args().skip(1).collect::<Vec<_>>();
+    match run(&args) {
+        Ok(output) => println!("{}", output),
+        Err(e) => {
+            eprintln!("error: {}\n\n{}", e, USAGE);
+            process::exit(1);
+        }
+    }
+}
+
+fn run(args: &[String]) -> Result<String, String> {
+    let options = parse_args(args)?;
+    if options.command == "help" {
+        return Ok(USAGE.to_string());
+    }
+    if options.command == "batch" {
+        return run_batch(&options);
+    }
+    let board = read_board(&options)?;
+    let player = options.player.unwrap_or_else(|| to_move(&board));
+    match options.command.as_str() {
+        "solve" => run_solve(&options, &board, player),
+        "forbiddens" => Ok(run_forbiddens(&options, &board)),
+        "threats" => Ok(run_threats(&options, &board)),
+        "status" => run_status(&options, &board, player),
+        "verify" => run_verify(&options, &board, player),
+        "render" => Ok(run_render(&options, &board)),
+        _ => unreachable!(),
+    }
+}
+
+fn parse_args(args: &[String]) -> Result<Options, String> {
+    let mut options = Options {
+        command: "help".to_string(),
+        mode: None,
+        limit: None,
+        threat_limit: DEFAULT_THREAT_LIMIT,
+        order: None,
+        player: None,
+        notation: Notation::Alphanumeric,
+        file: None,
+        snapshot: None,
+        db: None,
+        path: None,
+        input: None,
+        threads: None,
+        json: false,
+    };
+    let mut rest = args.iter();
+    match rest.next() {
+        Some(c) if c == "-h" || c == "--help" => return Ok(options),
+        Some(c) if COMMANDS.contains(&c.as_str()) => options.command = c.clone(),
+        Some(c) => return Err(format!("Unknown command: {}", c)),
+        None => return Ok(options),
+    }
+    while let Some(arg) = rest.next() {
+        let mut value = || {
+            rest.next()
+                .cloned()
+                .ok_or_else(|| format!("Missing value for {}", arg))
+        };
+        match arg.as_str() {
+            "-m" | "--mode" => options.mode = Some(value()?.parse()?),
+            "-l" | "--limit" => options.limit = Some(parse_u8(&value()?)?),
+            "-t" | "--threat-limit" => options.threat_limit = parse_u8(&value()?)?,
+            "--order" => options.order = Some(parse_u8(&value()?)?),
+            "-p" | "--player" => options.player = Some(value()?.parse()?),
+            "-n" | "--notation" => options.notation = value()?.parse()?,
+            "-f" | "--file" => options.file = Some(value()?),
+            "--path" => options.path = Some(value()?),
+            "--snapshot" => options.snapshot = Some(value()?),
+            "--db" => options.db = Some(value()?),
+            "--threads" => {
+                let v = value()?;
+                options.threads = Some(v.parse().map_err(|e| format!("{}: {}", e, v))?)
+            }
+            "--json" => options.json = true,
+            "-h" | "--help" => options.command = "help".to_string(),
+            s if s.starts_with('-') && s.len() > 1 => return Err(format!("Unknown option: {}", s)),
+            s if options.input.is_none() => options.input = Some(s.to_string()),
+            s => return Err(format!("Unexpected argument: {}", s)),
+        }
+    }
+    Ok(options)
+}
+
+fn parse_u8(s: &str) -> Result<u8, String> {
+    s.parse::<u8>().map_err(|e| format!("{}: {}", e, s))
+}
+
+fn read_board(options: &Options) -> Result<Board, String> {
+    let source = read_input(options)?;
+    Ok(Board::from_str_with(&source, options.notation)?)
+}
+
+fn read_input(options: &Options) -> Result<String, String> {
+    let source = match (&options.input, options.file.as_deref()) {
+        (Some(s), _) if options.command == "batch" => {
+            fs::read_to_string(s).map_err(|e| e.to_string())?
+        }
+        (Some(s), _) => s.clone(),
+        (None, Some(path)) if path != "-" => fs::read_to_string(path).map_err(|e| e.to_string())?,
+        (None, _) => {
+            let mut s = String::new();
+            io::stdin()
+                .read_to_string(&mut s)
+                .map_err(|e| e.to_string())?;
+            s
+        }
+    };
+    Ok(source)
+}
+
+fn to_move(board: &Board) -> Player {
+    let blacks = board.stones(Player::Black).count();
+    let whites = board.stones(Player::White).count();
+    if blacks > whites {
+        Player::White
+    } else {
+        Player::Black
+    }
+}
+
+fn run_solve(options: &Options, board: &Board, attacker: Player) -> Result<String, String> {
+    let mode = options.mode.unwrap_or(SolveMode::VCFDFS);
+    let limit = options.limit.unwrap_or(default_limit(mode));
+    if options.order.is_some() {
+        if mode != SolveMode::VCTLAMBDA {
+            return Err("--order is only for vct_lambda".to_string());
+        }
+        // Snapshots and solved positions do not record the order
+        if options.snapshot.is_some() || options.db.is_some() {
+            return Err("--order cannot be used with --snapshot or --db".to_string());
+        }
+    }
+    let start = Instant::now();
+    let solution = match (&options.snapshot, &options.db) {
+        (Some(_), Some(_)) => return Err("Both --snapshot and --db given".to_string()),
+        (None, Some(path)) => {
+            let mut db = Database::open(path)?;
+            db.solve(mode, limit, board, attacker, options.threat_limit)?
+        }
+        (Some(path), None) => {
+            let mut snapshot = if Path::new(path).exists() {
+                Snapshot::load(path)?
+            } else {
+                Snapshot::new(attacker, options.threat_limit)
+            };
+            let result = try_solve_with(
+                mode,
+                limit,
+                board,
+                attacker,
+                options.threat_limit,
+                &mut snapshot,
+            )?;
+            snapshot.save(path)?;
+            result
+        }
+        (None, None) if mode == SolveMode::VCTLAMBDA => {
+            let order = options.order.unwrap_or(DEFAULT_LAMBDA_ORDER);
+            try_solve_lambda(order, limit, board, attacker, options.threat_limit)?
+        }
+        (None, None) => try_solve(mode, limit, board, attacker, options.threat_limit)?,
+    };
+    let elapsed = start.elapsed();
+    if options.json {
+        let result = match &solution {
+            Some(m) => json!({
+                "found": true,
+                "end": m.end,
+                "path": Points(m.path.clone()).to_string_with(options.notation),
+                "n_times": m.n_times(),
+                "n_moves": m.n_moves(),
+                "elapsed_ms": elapsed.as_millis() as u64,
+            }),
+            None => json!({
+                "found": false,
+                "elapsed_ms": elapsed.as_millis() as u64,
+            }),
+        };
+        return Ok(result.to_string());
+    }
+    let result = match solution {
+        Some(m) => format!(
+            "End: {}\nTimes (Length): {} ({})\nMoves: {}\nElapsed: {:?}",
+            m.end,
+            m.n_times(),
+            m.n_moves(),
+            Points(m.path).to_string_with(options.notation),
+            elapsed
+        ),
+        None => format!("None\nElapsed: {:?}", elapsed),
+    };
+    Ok(result)
+}
+
+fn default_limit(mode: SolveMode) -> u8 {
+    match mode {
+        SolveMode::VCFDFS | SolveMode::VCFIDDFS => u8::MAX,
+        _ => 5,
+    }
+}
+
+fn run_forbiddens(options: &Options, board: &Board) -> String {
+    let forbiddens = board.forbiddens();
+    let notation = options.notation;
+    if options.json {
+        let result: Vec<_> = forbiddens
+            .iter()
+            .map(|(k, p)| json!({"point": p.to_string_with(notation), "kind": k}))
+            .collect();
+        return json!(result).to_string();
+    }
+    forbiddens
+        .iter()
+        .map(|(k, p)| format!("{} {:?}", p.to_string_with(notation), k))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn run_threats(options: &Options, board: &Board) -> String {
+    let players = match options.player {
+        Some(r) => vec![r],
+        None => vec![Player::Black, Player::White],
+    };
+    let threats: Vec<_> = players.into_iter().flat_map(|r| board.threats(r)).collect();
+    let notation = options.notation;
+    let format = |ps: &[Point]| Points(ps.to_vec()).to_string_with(notation);
+    if options.json {
+        let result: Vec<_> = threats
+            .iter()
+            .map(|t| {
+                json!({
+                    "player": t.player,
+                    "kind": format!("{:?}", t.kind),
+                    "direction": format!("{:?}", t.direction),
+                    "stones": format(&t.stones),
+                    "eyes": format(&t.eyes),
+                    "real": t.real,
+                })
+            })
+            .collect();
+        return json!(result).to_string();
+    }
+    threats
+        .iter()
+        .map(|t| {
+            let fake = if t.real { "" } else { " (fake)" };
+            format!(
+                "{:?} {:?} {:?} stones: {} eyes: {}{}",
+                t.player,
+                t.kind,
+                t.direction,
+                format(&t.stones),
+                format(&t.eyes),
+                fake
+            )
+        })
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+fn run_status(options: &Options, board: &Board, to_move: Player) -> Result<String, String> {
+    let default = StatusOptions::default();
+    let status_options = StatusOptions {
+        vct_limit: options.limit.unwrap_or(default.vct_limit),
+        vct_mode: match options.mode {
+            Some(SolveMode::VCFDFS) | None => default.vct_mode,
+            Some(mode) => mode,
+        },
+        threat_limit: options.threat_limit,
+        ..default
+    };
+    let result = status(board, to_move, &status_options);
+    let notation = options.notation;
+    let format_mate = |m: Option<&Mate>| m.map(|m| Points(m.path.clone()).to_string_with(notation));
+    let format = |ps: &[Point]| Points(ps.to_vec()).to_string_with(notation);
+    let forbiddens: Vec<_> = result.forbiddens.iter().map(|&(_, p)| p).collect();
+    if options.json {
+        let output = json!({
+            "to_move": result.to_move,
+            "vcf": format_mate(result.vcf.as_ref()),
+            "vct": format_mate(result.vct.as_ref()),
+            "threat_vcf": format_mate(result.threat_vcf.as_ref()),
+            "threat_vct": format_mate(result.threat_vct.as_ref()),
+            "forbiddens": format(&forbiddens),
+            "fours": format(&result.fours),
+            "threat_fours": format(&result.threat_fours),
+        });
+        return Ok(output.to_string());
+    }
+    let none = || "None".to_string();
+    let lines = [
+        format!("ToMove: {:?}", result.to_move),
+        format!(
+            "VCF: {}",
+            format_mate(result.vcf.as_ref()).unwrap_or_else(none)
+        ),
+        format!(
+            "VCT: {}",
+            format_mate(result.vct.as_ref()).unwrap_or_else(none)
+        ),
+        format!(
+            "ThreatVCF: {}",
+            format_mate(result.threat_vcf.as_ref()).unwrap_or_else(none)
+        ),
+        format!(
+            "ThreatVCT: {}",
+            format_mate(result.threat_vct.as_ref()).unwrap_or_else(none)
+        ),
+        format!("Forbiddens: {}", format(&forbiddens)),
+        format!("Fours: {}", format(&result.fours)),
+        format!("ThreatFours: {}", format(&result.threat_fours)),
+    ];
+    Ok(lines.join("\n"))
+}
+
+fn run_verify(options: &Options, board: &Board, attacker: Player) -> Result<String, String> {
+    let path = options.path.as_ref().ok_or("Missing --path")?;
+    let path = Points::from_str_with(path, options.notation)?;
+    let result = verify(board, attacker, &path.0, options.threat_limit);
+    if options.json {
+        let output = match &result {
+            Ok(end) => json!({"valid": true, "end": end}),
+            Err(reason) => json!({"valid": false, "reason": reason}),
+        };
+        return Ok(output.to_string());
+    }
+    let output = match result {
+        Ok(end) => format!("Valid: {}", end),
+        Err(reason) => format!("Invalid: {}", reason),
+    };
+    Ok(output)
+}
+
+// Replays the path alternately from the attacker.
+// Every attack but the last must make a threat, and every defence must block the four or stop the threat.
+fn verify(
+    board: &Board,
+    attacker: Player,
+    path: &[Point],
+    threat_limit: u8,
+) -> Result<End, String> {
+    let defender = attacker.opponent();
+    let mut board = board.clone();
+    let mut r = attacker;
+    let mut defences = vec![];
+    for (i, &p) in path.iter().enumerate() {
+        if board.stone(p).is_some() {
+            return Err(format!("{} is not empty", p));
+        }
+        if r.is_black() && board.forbidden(p).is_some() {
+            return Err(format!("{} is forbidden", p));
+        }
+        let eyes = four_eyes(&board, r.opponent());
+        if !eyes.is_empty() && !eyes.contains(&p) {
+            return Err(format!("{} does not block the four", p));
+        }
+        if r == defender && eyes.is_empty() && !defences.contains(&p) {
+            return Err(format!("{} does not stop the threat", p));
+        }
+        board.put_mut(r, p);
+        if board.structures(r, StructureKind::Five).next().is_some() {
+            return if r == attacker && i + 1 == path.len() {
+                Ok(End::Unknown)
+            } else {
+                Err(format!("{:?} makes five at {}", r, p))
+            };
+        }
+        if r == attacker && i + 1 < path.len() {
+            defences = threat_defences(&board, attacker, threat_limit)
+                .ok_or_else(|| format!("{} makes no threat", p))?;
+        }
+        r = r.opponent();
+    }
+    if r == attacker {
+        return Err("The last move is not an attack".to_string());
+    }
+    if board.structures(defender, Four).next().is_some() {
+        return Err(format!("{:?} can make five", defender));
+    }
+    match four_eyes(&board, attacker).as_slice() {
+        [e1, e2, ..] => Ok(End::Fours(*e1, *e2)),
+        [e] if defender.is_black() && board.forbidden(*e).is_some() => Ok(End::Forbidden(*e)),
+        _ => Err(format!("{:?} can defend", defender)),
+    }
+}
+
+fn four_eyes(board: &Board, r: Player) -> Vec<Point> {
+    let mut result: Vec<_> = board.structures(r, Four).flat_map(|s| s.eyes()).collect();
+    result.sort_by_key(|&p| u8::from(p));
+    result.dedup();
+    result
+}
+
+fn run_render(options: &Options, board: &Board) -> String {
+    let notation = options.notation;
+    let blacks = Points(board.stones(Player::Black).collect()).to_string_with(notation);
+    let whites = Points(board.stones(Player::White).collect()).to_string_with(notation);
+    if options.json {
+        let output = json!({
+            "blacks": blacks,
+            "whites": whites,
+            "pretty": board.to_pretty_string(),
+        });
+        return output.to_string();
+    }
+    format!("{}\n\n{}/{}", board.to_pretty_string(), blacks, whites)
+}
+
+fn run_batch(options: &Options) -> Result<String, String> {
+    let problems = batch::parse(&read_input(options)?)?;
+    let n_threads = options
+        .threads
+        .unwrap_or_else(|| std::thread::available_parallelism().map_or(1, |n| n.get()));
+    let outcomes = batch::run(&problems, n_threads);
+    let summary = batch::summarize(&outcomes);
+    if options.json {
+        let result = json!({
+            "outcomes": outcomes,
+            "summary": summary,
+        });
+        return Ok(result.to_string());
+    }
+    let mut lines = outcomes
+        .iter()
+        .map(|o| {
+            let result = match (&o.error, &o.path) {
+                (Some(e), _) => format!("Error: {}", e),
+                (None, Some(path)) => path.clone(),
+                (None, None) => "None".to_string(),
+            };
+            let mark = match o.matched {
+                Some(true) => " (ok)",
+                Some(false) => " (mismatch)",
+                None => "",
+            };
+            let stats = o.stats.map_or("-".to_string(), |s| {
+                format!("{}/{}/{}", s.entries, s.inserts, s.evictions)
+            });
+            format!(
+                "{}\t{}{}\t{:.1}ms\t{}",
+                o.id, result, mark, o.elapsed_ms, stats
+            )
+        })
+        .collect::<Vec<_>>();
+    lines.push(format!(
+        "Total: {}, Found: {}, Matched: {}, Mismatched: {}, Errors: {}, Elapsed: {:.1}ms",
+        summary.total,
+        summary.found,
+        summary.matched,
+        summary.mismatched,
+        summary.errors,
+        summary.elapsed_ms
+    ));
+    Ok(lines.join("\n"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn args(s: &str) -> Vec<String> {
+        s.split_whitespace().map(|a| a.to_string()).collect()
+    }
+
+    #[test]
+    fn test_parse_args() -> Result<(), String> {
+        let options = parse_args(&args("solve -m vct -l 4 -p black --json H8,I9"))?;
+        assert_eq!(options.command, "solve");
+        assert_eq!(options.mode, Some(SolveMode::VCTDFS));
+        assert_eq!(options.limit, Some(4));
+        assert_eq!(options.player, Some(Player::Black));
+        assert_eq!(options.input.as_deref(), Some("H8,I9"));
+        assert!(options.json);
+        assert_eq!(options.order, None);
+
+        let options = parse_args(&args("solve -m vct_lambda --order 2"))?;
+        assert_eq!(options.order, Some(2));
+
+        assert!(parse_args(&args("bogus")).is_err());
+        assert!(parse_args(&args("solve --limit")).is_err());
+        assert!(parse_args(&args("solve --unknown")).is_err());
+        assert!(parse_args(&args("solve -l 256")).is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_run() -> Result<(), String> {
+        let board = "H10,H9,J9,H8,I7/I11,I9,G8,J8,H7";
+
+        let result = run(&args(&format!(
+            "solve -m vct_dfpns -l 4 -t 1 --json {}",
+            board
+        )))?;
+        assert!(result.contains(r#""path":"F10,G9,I10,G10,H11,H12,G12""#));
+
+        let result = run(&args(&format!(
+            "solve -m vct_lambda --order 2 -l 4 -t 1 --json {}",
+            board
+        )))?;
+        assert!(result.contains(r#""path":"F10,G9,I10,G10,H11,H12,G12""#));
+
+        let result = run(&args(&format!("solve -m vct_lambda --order 0 {}", board)));
+        assert_eq!(
+            result,
+            Err("Order of lambda search must be positive".to_string())
+        );
+
+        let result = run(&args(&format!("solve -m vct_dfpns --order 2 {}", board)));
+        assert_eq!(result, Err("--order is only for vct_lambda".to_string()));
+
+        let result = run(&args(&format!(
+            "solve -m vct_lambda --order 2 --db quintet.db {}",
+            board
+        )));
+        assert_eq!(
+            result,
+            Err("--order cannot be used with --snapshot or --db".to_string())
+        );
+
+        let snapshot = env::temp_dir().join(format!("quintet-{}.snapshot", process::id()));
+        let command = format!(
+            "solve -m vct_dfpns -l 4 -t 1 -p o --snapshot {} {}",
+            snapshot.display(),
+            board
+        );
+        let first = run(&args(&command));
+        let second = run(&args(&command));
+        fs::remove_file(&snapshot).map_err(|e| e.to_string())?;
+        assert!(first?.contains("Moves: F10,G9,I10,G10,H11,H12,G12"));
+        assert!(second?.contains("Moves: F10,G9,I10,G10,H11,H12,G12"));
+
+        let db = env::temp_dir().join(format!("quintet-{}.db", process::id()));
+        let command = format!(
+            "solve -m vct_dfpns -l 4 -t 1 -p o --db {} {}",
+            db.display(),
+            board
+        );
+        let first = run(&args(&command));
+        let second = run(&args(&command));
+        let records = fs::read_to_string(&db).map_err(|e| e.to_string());
+        fs::remove_file(&db).map_err(|e| e.to_string())?;
+        assert!(first?.contains("Moves: F10,G9,I10,G10,H11,H12,G12"));
+        assert!(second?.contains("Moves: F10,G9,I10,G10,H11,H12,G12"));
+        assert_eq!(records?.lines().count(), 1);
+
+        let result = run(&args(&format!("forbiddens {}", "H9,G8,I8,H7/")))?;
+        assert_eq!(result, "H8 DoubleThree");
+
+        let result = run(&args(&format!("threats -p x {}", board)))?;
+        assert_eq!(result, "");
+
+        let path = "--path F10,G9,I10,G10,H11,H12,G12";
+        let result = run(&args(&format!("verify {} {}", path, board)))?;
+        assert_eq!(result, "Valid: Fours(F13, K8)");
+
+        let result = run(&args(&format!("verify --path F10,G9 {}", board)))?;
+        assert_eq!(result, "Invalid: The last move is not an attack");
+
+        let path = "--path F10,G9,I10,F8,H11,H12,G12";
+        let result = run(&args(&format!("verify {} {}", path, board)))?;
+        assert_eq!(result, "Invalid: F8 does not stop the threat");
+
+        let path = "--path A1,G9,I10,G10,H11,H12,G12";
+        let result = run(&args(&format!("verify {} {}", path, board)))?;
+        assert_eq!(result, "Invalid: A1 makes no threat");
+
+        let path = env::temp_dir().join(format!("quintet-batch-{}.csv", process::id()));
+        fs::write(
+            &path,
+            format!(
+                "id,mode,limit,attacker,threat_limit,board\nokabe02,vct_dfpns,4,o,1,\"{}\"\n",
+                board
+            ),
+        )
+        .map_err(|e| e.to_string())?;
+        let result = run(&args(&format!("batch --threads 2 {}", path.display())));
+        fs::remove_file(&path).map_err(|e| e.to_string())?;
+        assert!(result?.starts_with("okabe02\tF10,G9,I10,G10,H11,H12,G12\t"));
+
+        Ok(())
+    }
+}
@@ -1,19 +1,25 @@
 mod board;
 mod forbidden;
 mod line;
+mod notation;
 mod player;
 mod point;
 mod potential;
 mod sequence;
 mod square;
 mod structure;
+mod symmetry;
+mod threat;
 mod zobrist;
 
 pub use board::Board;
 pub use forbidden::ForbiddenKind;
 pub use line::Line;
+pub use notation::Notation;
 pub use player::Player;
 pub use point::{Direction, Index, Point, Points, RANGE};
 pub use potential::{Potentials, VICTORY};
 pub use square::Square;
 pub use structure::{Structure, StructureKind};
+pub use symmetry::Symmetry;
+pub use threat::{Threat, ThreatKind};
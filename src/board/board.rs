@@ -1,8 +1,10 @@
 use super::forbidden::*;
+use super::notation::*;
 use super::player::*;
 use super::point::*;
 use super::square::*;
 use super::structure::*;
+use super::threat::*;
 use super::zobrist;
 use std::fmt;
 use std::str::FromStr;
@@ -30,6 +32,31 @@ impl Board {
         }
     }
 
+    pub fn from_moves(moves: &Points) -> Self {
+        let square = Square::from_moves(moves);
+        let blacks = square.stones(Black).collect();
+        let whites = square.stones(White).collect();
+        let z_hash = zobrist::from_stones(&Points(blacks), &Points(whites));
+        Self {
+            square: square,
+            z_hash: z_hash,
+        }
+    }
+
+    pub fn from_str_with(s: &str, notation: Notation) -> Result<Self, &'static str> {
+        let s = s.trim();
+        if let Some((blacks_str, whites_str)) = s.split_once('/') {
+            let blacks = notation.parse_points(blacks_str)?;
+            let whites = notation.parse_points(whites_str)?;
+            Ok(Self::from_stones(&blacks, &whites))
+        } else if s.contains('\n') {
+            s.parse()
+        } else {
+            let moves = notation.parse_points(s)?;
+            Ok(Self::from_moves(&moves))
+        }
+    }
+
     pub fn put_mut(&mut self, r: Player, p: Point) {
         self.remove_mut(p);
         self.square.put_mut(r, p);
@@ -122,6 +149,14 @@ impl Board {
         forbidden(&self.square, p)
     }
 
+    pub fn threats(&self, r: Player) -> Vec<Threat> {
+        threats(&self.square, r)
+    }
+
+    pub fn threats_on(&self, p: Point, r: Player) -> Vec<Threat> {
+        threats_on(&self.square, p, r)
+    }
+
     pub fn zobrist_hash(&self) -> u64 {
         self.z_hash
     }
@@ -135,6 +170,32 @@ impl Board {
     }
 }
 
+#[cfg(feature = "serde")]
+#[derive(serde::Serialize, serde::Deserialize)]
+struct Stones {
+    blacks: Points,
+    whites: Points,
+}
+
+#[cfg(feature = "serde")]
+impl serde::Serialize for Board {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        let stones = Stones {
+            blacks: Points(self.stones(Black).collect()),
+            whites: Points(self.stones(White).collect()),
+        };
+        stones.serialize(serializer)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Board {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let stones = Stones::deserialize(deserializer)?;
+        Ok(Self::from_stones(&stones.blacks, &stones.whites))
+    }
+}
+
 impl fmt::Display for Board {
     fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         f.write_str(&self.square.to_string())
@@ -162,6 +223,23 @@ mod tests {
     use super::super::sequence::*;
     use super::*;
 
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_serde() -> Result<(), String> {
+        let board = Board::from_moves(&"H8,I9,H10".parse::<Points>()?);
+        let json = serde_json::to_string(&board).map_err(|e| e.to_string())?;
+        assert_eq!(json, r#"{"blacks":["H8","H10"],"whites":["I9"]}"#);
+
+        let result: Board = serde_json::from_str(&json).map_err(|e| e.to_string())?;
+        assert_eq!(result.zobrist_hash(), board.zobrist_hash());
+        assert_eq!(result.to_string(), board.to_string());
+
+        let result: Result<Point, _> = serde_json::from_str(r#""Z99""#);
+        assert!(result.is_err());
+
+        Ok(())
+    }
+
     #[test]
     fn test() -> Result<(), String> {
         let mut board = Board::new();
@@ -344,6 +422,21 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn test_from_str_with() -> Result<(), String> {
+        let expected = "H8,J9/I9".parse::<Board>()?;
+
+        let result = Board::from_str_with("h8i9j9", Lowercase)?;
+        assert_eq!(result.square, expected.square);
+        assert_eq!(result.z_hash, expected.z_hash);
+
+        let result = Board::from_str_with("8,8 10,9/9,9", NumericXY)?;
+        assert_eq!(result.square, expected.square);
+        assert_eq!(result.z_hash, expected.z_hash);
+
+        Ok(())
+    }
+
     fn trim_lines_string(s: &str) -> String {
         s.trim()
             .split("\n")
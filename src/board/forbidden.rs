@@ -4,6 +4,7 @@ use super::square::*;
 use super::structure::*;
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum ForbiddenKind {
     DoubleThree,
     DoubleFour,
@@ -12,6 +13,16 @@ pub enum ForbiddenKind {
 
 pub use ForbiddenKind::*;
 
+impl From<ForbiddenKind> for u8 {
+    fn from(value: ForbiddenKind) -> u8 {
+        match value {
+            DoubleThree => 1,
+            DoubleFour => 2,
+            Overline => 3,
+        }
+    }
+}
+
 pub fn forbiddens(q: &Square) -> Vec<(ForbiddenKind, Point)> {
     q.empties()
         .map(|p| (forbidden_strict(q, p), p))
@@ -0,0 +1,252 @@
+use super::point::*;
+use std::convert::TryFrom;
+use std::str::FromStr;
+
+const SKIP_I_LETTERS: &[u8; RANGE as usize] = b"ABCDEFGHJKLMNOP";
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Notation {
+    Alphanumeric, // H8,I9,J10
+    Lowercase,    // h8i9j10
+    SkipI,        // H8,J9,K10
+    NumericXY,    // 8,8 9,9 10,10
+    NumericXY0,   // 7,7 8,8 9,9
+    NumericYX,    // 8,8 9,9 10,10 (row first)
+    NumericYX0,   // 7,7 8,8 9,9 (row first)
+}
+
+pub use Notation::*;
+
+impl Notation {
+    pub fn parse_point(&self, s: &str) -> Result<Point, &'static str> {
+        let s = s.trim();
+        match self {
+            Alphanumeric | Lowercase => s.parse::<Point>(),
+            SkipI => {
+                let mut cs = s.chars();
+                let c = cs.next().ok_or("Failed to parse x part.")?;
+                let x = SKIP_I_LETTERS
+                    .iter()
+                    .position(|&l| l as char == c.to_ascii_uppercase())
+                    .ok_or("Failed to parse x part.")?;
+                let y = parse_coordinate(cs.as_str(), 1).ok_or("Failed to parse y part.")?;
+                Ok(Point(x as u8, y))
+            }
+            NumericXY | NumericXY0 | NumericYX | NumericYX0 => {
+                let mut parts = s.split(',');
+                let first = parts.next().ok_or("Wrong format.")?;
+                let second = parts.next().ok_or("Wrong format.")?;
+                if parts.next().is_some() {
+                    return Err("Wrong format.");
+                }
+                let base = self.numeric_base();
+                let a = parse_coordinate(first, base).ok_or("Failed to parse coordinate.")?;
+                let b = parse_coordinate(second, base).ok_or("Failed to parse coordinate.")?;
+                if self.row_first() {
+                    Ok(Point(b, a))
+                } else {
+                    Ok(Point(a, b))
+                }
+            }
+        }
+    }
+
+    pub fn format_point(&self, p: Point) -> String {
+        match self {
+            Alphanumeric => p.to_string(),
+            Lowercase => p.to_string().to_lowercase(),
+            SkipI => format!("{}{}", SKIP_I_LETTERS[p.0 as usize] as char, p.1 + 1),
+            NumericXY | NumericXY0 | NumericYX | NumericYX0 => {
+                let base = self.numeric_base();
+                let (a, b) = if self.row_first() {
+                    (p.1, p.0)
+                } else {
+                    (p.0, p.1)
+                };
+                format!("{},{}", a + base, b + base)
+            }
+        }
+    }
+
+    pub fn parse_points(&self, s: &str) -> Result<Points, &'static str> {
+        let s = s.trim();
+        if s.is_empty() {
+            return Ok(Points(vec![]));
+        }
+        let ps = match self {
+            Alphanumeric | SkipI => s
+                .split(',')
+                .map(|m| self.parse_point(m))
+                .collect::<Result<Vec<_>, _>>()?,
+            Lowercase => split_compact(s)
+                .iter()
+                .map(|m| self.parse_point(m))
+                .collect::<Result<Vec<_>, _>>()?,
+            NumericXY | NumericXY0 | NumericYX | NumericYX0 => split_numeric(s)
+                .iter()
+                .map(|m| self.parse_point(m))
+                .collect::<Result<Vec<_>, _>>()?,
+        };
+        Ok(Points(ps))
+    }
+
+    pub fn format_points(&self, ps: &Points) -> String {
+        let separator = match self {
+            Alphanumeric | SkipI => ",",
+            Lowercase => "",
+            NumericXY | NumericXY0 | NumericYX | NumericYX0 => " ",
+        };
+        ps.0.iter()
+            .map(|&p| self.format_point(p))
+            .collect::<Vec<_>>()
+            .join(separator)
+    }
+
+    fn numeric_base(&self) -> u8 {
+        match self {
+            NumericXY0 | NumericYX0 => 0,
+            _ => 1,
+        }
+    }
+
+    fn row_first(&self) -> bool {
+        matches!(self, NumericYX | NumericYX0)
+    }
+}
+
+// Codes of wasm and ffi: letter notations count from 0 and numeric ones from 10,
+// so that either group can grow without renumbering the other
+impl TryFrom<u8> for Notation {
+    type Error = &'static str;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(Alphanumeric),
+            1 => Ok(Lowercase),
+            2 => Ok(SkipI),
+            10 => Ok(NumericXY),
+            11 => Ok(NumericXY0),
+            12 => Ok(NumericYX),
+            13 => Ok(NumericYX0),
+            _ => Err("Unknown notation"),
+        }
+    }
+}
+
+impl FromStr for Notation {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "alphanumeric" => Ok(Alphanumeric),
+            "lowercase" => Ok(Lowercase),
+            "skip_i" => Ok(SkipI),
+            "xy" => Ok(NumericXY),
+            "xy0" => Ok(NumericXY0),
+            "yx" => Ok(NumericYX),
+            "yx0" => Ok(NumericYX0),
+            _ => Err("Unknown notation"),
+        }
+    }
+}
+
+fn parse_coordinate(s: &str, base: u8) -> Option<u8> {
+    let n = s.trim().parse::<u8>().ok()?;
+    if base <= n && n < RANGE + base {
+        Some(n - base)
+    } else {
+        None
+    }
+}
+
+// Splits "8,8 9, 9;10 ,10" into points, joining spaces around commas
+fn split_numeric(s: &str) -> Vec<String> {
+    let mut result: Vec<String> = vec![];
+    for m in s.split(|c: char| c == ';' || c.is_whitespace()) {
+        if m.is_empty() {
+            continue;
+        }
+        match result.last_mut() {
+            Some(last) if last.ends_with(',') || m.starts_with(',') => last.push_str(m),
+            _ => result.push(m.to_string()),
+        }
+    }
+    result
+}
+
+fn split_compact(s: &str) -> Vec<&str> {
+    let mut result = vec![];
+    let mut start = 0;
+    for (i, c) in s.char_indices().skip(1) {
+        if c.is_ascii_alphabetic() {
+            result.push(&s[start..i]);
+            start = i;
+        }
+    }
+    result.push(&s[start..]);
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_point() -> Result<(), String> {
+        assert_eq!(Alphanumeric.parse_point("H8")?, Point(7, 7));
+        assert_eq!(Lowercase.parse_point("j10")?, Point(9, 9));
+        assert_eq!(SkipI.parse_point("J9")?, Point(8, 8));
+        assert_eq!(SkipI.parse_point("p15")?, Point(14, 14));
+        assert_eq!(NumericXY.parse_point("4,6")?, Point(3, 5));
+        assert_eq!(NumericXY0.parse_point("4,6")?, Point(4, 6));
+        assert_eq!(NumericYX.parse_point("4,6")?, Point(5, 3));
+        assert_eq!(NumericYX0.parse_point("4,6")?, Point(6, 4));
+
+        assert!(SkipI.parse_point("I8").is_err());
+        assert!(NumericXY.parse_point("0,8").is_err());
+        assert!(NumericXY0.parse_point("15,8").is_err());
+        assert_eq!(NumericXY.parse_point(" 4, 6 ")?, Point(3, 5));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_format_point() {
+        let p = Point(8, 5);
+        assert_eq!(Alphanumeric.format_point(p), "I6");
+        assert_eq!(Lowercase.format_point(p), "i6");
+        assert_eq!(SkipI.format_point(p), "J6");
+        assert_eq!(NumericXY.format_point(p), "9,6");
+        assert_eq!(NumericXY0.format_point(p), "8,5");
+        assert_eq!(NumericYX.format_point(p), "6,9");
+        assert_eq!(NumericYX0.format_point(p), "5,8");
+    }
+
+    #[test]
+    fn test_points() -> Result<(), String> {
+        let expected = vec![Point(7, 7), Point(8, 8), Point(9, 9)];
+
+        let result = Lowercase.parse_points("h8i9j10")?;
+        assert_eq!(result.0, expected);
+        assert_eq!(Lowercase.format_points(&result), "h8i9j10");
+
+        let result = SkipI.parse_points("H8,J9,K10")?;
+        assert_eq!(result.0, expected);
+        assert_eq!(SkipI.format_points(&result), "H8,J9,K10");
+
+        let result = NumericXY.parse_points("8,8 9,9;10,10")?;
+        assert_eq!(result.0, expected);
+        assert_eq!(NumericXY.format_points(&result), "8,8 9,9 10,10");
+
+        let result = NumericXY.parse_points("8, 8; 9 ,9 10 , 10")?;
+        assert_eq!(result.0, expected);
+
+        assert!(NumericXY.parse_points("8,8 9").is_err());
+        assert!(NumericXY.parse_points("8,8,9").is_err());
+
+        let result = NumericYX0.parse_points("")?;
+        assert!(result.0.is_empty());
+
+        Ok(())
+    }
+}
@@ -2,6 +2,7 @@ use std::convert::{From, TryFrom};
 use std::str::FromStr;
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum Player {
     Black,
     White,
@@ -64,11 +65,36 @@ impl From<Player> for char {
     }
 }
 
+// Accepts "o", "x", "black" and "white" in any case
 impl FromStr for Player {
     type Err = &'static str;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        let c = s.trim().chars().next().ok_or("empty")?;
+        let s = s.trim();
+        if s.eq_ignore_ascii_case("black") {
+            return Ok(Black);
+        }
+        if s.eq_ignore_ascii_case("white") {
+            return Ok(White);
+        }
+        let c = s.chars().next().ok_or("empty")?;
         Self::try_from(c)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_from_str() -> Result<(), String> {
+        assert_eq!("o".parse::<Player>()?, Black);
+        assert_eq!(" x ".parse::<Player>()?, White);
+        assert_eq!("oxo".parse::<Player>()?, Black);
+        assert_eq!("Black".parse::<Player>()?, Black);
+        assert_eq!("white".parse::<Player>()?, White);
+        assert!("".parse::<Player>().is_err());
+        assert!("b".parse::<Player>().is_err());
+        Ok(())
+    }
+}
@@ -1,3 +1,4 @@
+use super::notation::Notation;
 use std::convert::TryFrom;
 use std::fmt;
 use std::str::FromStr;
@@ -25,7 +26,30 @@ impl fmt::Display for Point {
     }
 }
 
+#[cfg(feature = "serde")]
+impl serde::Serialize for Point {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+#[cfg(feature = "serde")]
+impl<'de> serde::Deserialize<'de> for Point {
+    fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
 impl Point {
+    pub fn from_str_with(s: &str, notation: Notation) -> Result<Self, &'static str> {
+        notation.parse_point(s)
+    }
+
+    pub fn to_string_with(&self, notation: Notation) -> String {
+        notation.format_point(*self)
+    }
+
     pub fn to_index(&self, d: Direction) -> Index {
         let (x, y) = (self.0, self.1);
         let n = RANGE - 1;
@@ -167,9 +191,18 @@ impl fmt::Debug for Index {
 }
 
 #[derive(Debug)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Points(pub Vec<Point>);
 
 impl Points {
+    pub fn from_str_with(s: &str, notation: Notation) -> Result<Self, &'static str> {
+        notation.parse_points(s)
+    }
+
+    pub fn to_string_with(&self, notation: Notation) -> String {
+        notation.format_points(self)
+    }
+
     pub fn into_vec(self) -> Vec<Point> {
         self.0
     }
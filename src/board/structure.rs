@@ -1,6 +1,7 @@
 use super::player::*;
 use super::point::*;
 use super::sequence::*;
+use std::convert::TryFrom;
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
 pub enum StructureKind {
@@ -31,6 +32,24 @@ impl StructureKind {
     }
 }
 
+impl TryFrom<u8> for StructureKind {
+    type Error = &'static str;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            2 => Ok(Two),
+            3 => Ok(Three),
+            4 => Ok(OpenFour),
+            13 => Ok(Sword),
+            14 => Ok(Four),
+            15 => Ok(Five),
+            24 => Ok(NextOverFive),
+            25 => Ok(OverFive),
+            _ => Err("Unknown structure kind"),
+        }
+    }
+}
+
 #[derive(Debug, PartialEq, Eq, Clone)]
 pub struct Structure {
     start: Index,
@@ -0,0 +1,91 @@
+use super::board::Board;
+use super::player::*;
+use super::point::*;
+
+// One of the 8 symmetries of the board: bit 0 swaps x and y, bit 1 flips x and bit 2 flips y (applied in this order)
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct Symmetry(pub u8);
+
+impl Symmetry {
+    pub fn identity() -> Self {
+        Self(0)
+    }
+
+    pub fn all() -> impl Iterator<Item = Self> {
+        (0..8).map(Self)
+    }
+
+    pub fn apply(&self, p: Point) -> Point {
+        let n = RANGE - 1;
+        let Point(mut x, mut y) = p;
+        if self.0 & 1 != 0 {
+            std::mem::swap(&mut x, &mut y);
+        }
+        if self.0 & 2 != 0 {
+            x = n - x;
+        }
+        if self.0 & 4 != 0 {
+            y = n - y;
+        }
+        Point(x, y)
+    }
+
+    pub fn inverse(&self) -> Self {
+        let p = Point(0, 1);
+        Self::all().find(|s| s.apply(self.apply(p)) == p).unwrap()
+    }
+
+    pub fn apply_board(&self, board: &Board) -> Board {
+        let blacks = Points(board.stones(Black).map(|p| self.apply(p)).collect());
+        let whites = Points(board.stones(White).map(|p| self.apply(p)).collect());
+        Board::from_stones(&blacks, &whites)
+    }
+
+    // The symmetry that maps the board to the one of the least zobrist hash
+    pub fn canonical(board: &Board) -> (Self, u64) {
+        Self::all()
+            .map(|s| (s, s.apply_board(board).zobrist_hash()))
+            .min_by_key(|&(_, hash)| hash)
+            .unwrap()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::Notation;
+
+    #[test]
+    fn test_apply() {
+        let p = Point(2, 3);
+        let images: Vec<_> = Symmetry::all().map(|s| s.apply(p)).collect();
+        assert_eq!(
+            images,
+            [
+                Point(2, 3),
+                Point(3, 2),
+                Point(12, 3),
+                Point(11, 2),
+                Point(2, 11),
+                Point(3, 12),
+                Point(12, 11),
+                Point(11, 12),
+            ]
+        );
+        for s in Symmetry::all() {
+            assert_eq!(s.inverse().apply(s.apply(p)), p);
+        }
+    }
+
+    #[test]
+    fn test_canonical() -> Result<(), String> {
+        let board = Board::from_str_with("H8,J9/I9", Notation::Alphanumeric)?;
+        let (s, hash) = Symmetry::canonical(&board);
+        assert_eq!(s.apply_board(&board).zobrist_hash(), hash);
+        for t in Symmetry::all() {
+            let (_, other) = Symmetry::canonical(&t.apply_board(&board));
+            assert_eq!(other, hash);
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,219 @@
+use super::forbidden::*;
+use super::player::*;
+use super::point::*;
+use super::square::*;
+use super::structure::*;
+
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum ThreatKind {
+    StraightFour,
+    SimpleFour,
+    SolidThree,
+    SplitThree,
+}
+
+pub use ThreatKind::*;
+
+impl ThreatKind {
+    pub fn is_four(&self) -> bool {
+        matches!(self, StraightFour | SimpleFour)
+    }
+
+    pub fn is_three(&self) -> bool {
+        matches!(self, SolidThree | SplitThree)
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Threat {
+    pub player: Player,
+    pub kind: ThreatKind,
+    pub direction: Direction,
+    pub stones: Vec<Point>,
+    pub eyes: Vec<Point>,
+    pub real: bool,
+}
+
+pub fn threats(q: &Square, r: Player) -> Vec<Threat> {
+    let mut result = fours(q, r, q.structures(r, Four));
+    result.extend(threes(q, r, q.structures(r, Three)));
+    result
+}
+
+pub fn threats_on(q: &Square, p: Point, r: Player) -> Vec<Threat> {
+    let mut result = fours(q, r, q.structures_on(p, r, Four));
+    result.extend(threes(q, r, q.structures_on(p, r, Three)));
+    result.retain(|t| t.stones.contains(&p));
+    result
+}
+
+fn fours(q: &Square, r: Player, structures: impl Iterator<Item = Structure>) -> Vec<Threat> {
+    let groups = group(q, r, structures);
+    groups
+        .into_iter()
+        .map(|(direction, stones, eyes)| Threat {
+            player: r,
+            kind: if eyes.len() >= 2 {
+                StraightFour
+            } else {
+                SimpleFour
+            },
+            direction: direction,
+            stones: stones,
+            eyes: eyes,
+            real: true,
+        })
+        .collect()
+}
+
+fn threes(q: &Square, r: Player, structures: impl Iterator<Item = Structure>) -> Vec<Threat> {
+    let groups = group(q, r, structures);
+    groups
+        .into_iter()
+        .map(|(direction, stones, eyes)| {
+            let first = stones[0].to_index(direction).j;
+            let last = stones[stones.len() - 1].to_index(direction).j;
+            let real = r.is_white() || eyes.iter().any(|&e| forbidden(q, e).is_none());
+            Threat {
+                player: r,
+                kind: if last - first == 2 {
+                    SolidThree
+                } else {
+                    SplitThree
+                },
+                direction: direction,
+                stones: stones,
+                eyes: eyes,
+                real: real,
+            }
+        })
+        .collect()
+}
+
+fn group(
+    q: &Square,
+    r: Player,
+    structures: impl Iterator<Item = Structure>,
+) -> Vec<(Direction, Vec<Point>, Vec<Point>)> {
+    let mut result: Vec<(Index, Vec<Point>, Vec<Point>)> = vec![];
+    for s in structures {
+        let start = s.start_index();
+        // Compact structures pretend the last cell to be a stone
+        let stones: Vec<_> = s.stones().filter(|&p| q.stone(p) == Some(r)).collect();
+        let found = result
+            .iter_mut()
+            .find(|(i, ss, _)| i.d == start.d && i.i == start.i && *ss == stones);
+        if let Some((_, _, eyes)) = found {
+            for e in s.eyes() {
+                if !eyes.contains(&e) {
+                    eyes.push(e);
+                }
+            }
+        } else {
+            result.push((start, stones, s.eyes().collect()));
+        }
+    }
+    result
+        .into_iter()
+        .map(|(i, stones, eyes)| (i.d, stones, eyes))
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_threats() -> Result<(), String> {
+        let square = "
+         . . . . . . . . . . . . . . .
+         . . . . . . . . . . . . . . .
+         . . . . . . . . . . . . . . .
+         . . . . . . . . . . . . . . .
+         . . . . . . . . . . . . . . .
+         . . . . . . . . . . . . . . .
+         . . . . . . . . . . . . . . .
+         . . . . . . . . . . . . . . .
+         . . . . . . . . . . . . . . .
+         . . . . . . . . . . . . . . .
+         . . . . . . . . . . . . . . .
+         . . . . . . x . x x . . . . .
+         . . . . . . . . . . . . . . .
+         . . . o o o . . . . . . o . .
+         . . . . . . . . x x x x o . .
+        "
+        .parse::<Square>()?;
+
+        let result = threats(&square, White);
+        let expected = [
+            Threat {
+                player: White,
+                kind: SimpleFour,
+                direction: Horizontal,
+                stones: vec![Point(8, 0), Point(9, 0), Point(10, 0), Point(11, 0)],
+                eyes: vec![Point(7, 0)],
+                real: true,
+            },
+            Threat {
+                player: White,
+                kind: SplitThree,
+                direction: Horizontal,
+                stones: vec![Point(6, 3), Point(8, 3), Point(9, 3)],
+                eyes: vec![Point(7, 3)],
+                real: true,
+            },
+        ];
+        assert_eq!(result, expected);
+
+        let result = threats_on(&square, Point(8, 0), White);
+        assert_eq!(result, expected[..1]);
+
+        let result = threats(&square, Black);
+        let expected = [Threat {
+            player: Black,
+            kind: SolidThree,
+            direction: Horizontal,
+            stones: vec![Point(3, 1), Point(4, 1), Point(5, 1)],
+            eyes: vec![Point(2, 1), Point(6, 1)],
+            real: true,
+        }];
+        assert_eq!(result, expected);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_threats_fake_three() -> Result<(), String> {
+        let square = "
+         . . . . . . . . . . . . . . .
+         . . . . . . . . . . . . . . .
+         . . . . . . . . . . . . . . .
+         . . . . . . . . . . . . . . .
+         . . . . . . . . . . . . . . .
+         . . . . . . . x . . . . . . .
+         . . . . . . . o . . . . . . .
+         . . . . . . . o . . . . . . .
+         . . . . . . . o . . . . . . .
+         . . . . . . o . o o . . . . .
+         . . . . . . . . . . . . . . .
+         . . . . . . . . . . . . . . .
+         . . . . . . . . . . . . . . .
+         . . . . . . . . . . . . . . .
+         . . . . . . . . . . . . . . .
+        "
+        .parse::<Square>()?;
+
+        let result = threats(&square, Black);
+        let expected = [Threat {
+            player: Black,
+            kind: SplitThree,
+            direction: Horizontal,
+            stones: vec![Point(6, 5), Point(8, 5), Point(9, 5)],
+            eyes: vec![Point(7, 5)],
+            real: false,
+        }];
+        assert_eq!(result, expected);
+
+        Ok(())
+    }
+}
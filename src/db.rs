@@ -0,0 +1,433 @@
+use crate::board::*;
+use crate::mate::*;
+use std::collections::HashMap;
+use std::convert::TryInto;
+use std::fmt;
+use std::fs::{File, OpenOptions};
+use std::io::{BufRead, BufReader, Read, Seek, SeekFrom, Write};
+use std::str::FromStr;
+
+/*
+Solved positions are appended to a text file, one record per line:
+
+  <canonical hash in hex> <canonical blacks>/<canonical whites> <attacker o|x> <mode> <limit> <threat limit> <end> <path>
+
+where stones are "-" if none, end is "none", "unknown", "fours:F13,K8" or "forbidden:H8", and path is "-" if empty.
+Boards are canonicalized over the 8 symmetries, and points are stored in canonical coordinates.
+Stones are compared on lookup, so that boards of the same hash are told apart.
+
+Offsets of records by hash are appended to an index file "<path>.idx" as 24-byte entries:
+
+  <hash> <offset of the line> <offset of the next line>
+
+in little endian. Records not in the index yet, such as ones appended by hand, are indexed on open.
+A broken index file is rebuilt from the records.
+*/
+
+const INDEX_ENTRY_SIZE: usize = 24;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Record {
+    pub key: u64,
+    pub blacks: Vec<Point>,
+    pub whites: Vec<Point>,
+    pub attacker: Player,
+    pub mode: SolveMode,
+    pub limit: u8,
+    pub threat_limit: u8,
+    pub mate: Option<Mate>,
+}
+
+pub struct Database {
+    store: Store,
+    // Offsets of records in the file, or positions in memory
+    index: HashMap<u64, Vec<u64>>,
+}
+
+enum Store {
+    File { records: File, index: File },
+    Memory(Vec<Record>),
+}
+
+impl Database {
+    pub fn open(path: &str) -> Result<Self, String> {
+        let options = || {
+            let mut options = OpenOptions::new();
+            options.create(true).read(true).append(true);
+            options
+        };
+        let records = options().open(path).map_err(|e| e.to_string())?;
+        let index_path = format!("{}.idx", path);
+        let mut index = options().open(&index_path).map_err(|e| e.to_string())?;
+
+        let mut bytes = vec![];
+        index.read_to_end(&mut bytes).map_err(|e| e.to_string())?;
+        if bytes.len() % INDEX_ENTRY_SIZE != 0 {
+            index = File::create(&index_path).map_err(|e| e.to_string())?;
+            bytes.clear();
+        }
+        let mut result = Self {
+            store: Store::Memory(vec![]),
+            index: HashMap::new(),
+        };
+        let mut indexed = 0;
+        for entry in bytes.chunks(INDEX_ENTRY_SIZE) {
+            let field = |i: usize| u64::from_le_bytes(entry[i..i + 8].try_into().unwrap());
+            result.index.entry(field(0)).or_default().push(field(8));
+            indexed = indexed.max(field(16));
+        }
+
+        let mut reader = BufReader::new(&records);
+        reader
+            .seek(SeekFrom::Start(indexed))
+            .map_err(|e| e.to_string())?;
+        let mut offset = indexed;
+        let mut line = String::new();
+        loop {
+            line.clear();
+            let n = reader.read_line(&mut line).map_err(|e| e.to_string())?;
+            if n == 0 {
+                break;
+            }
+            let next = offset + n as u64;
+            if !line.trim().is_empty() {
+                let record = line
+                    .parse::<Record>()
+                    .map_err(|e| format!("offset {}: {}", offset, e))?;
+                write_index_entry(&mut index, record.key, offset, next)?;
+                result.index.entry(record.key).or_default().push(offset);
+            }
+            offset = next;
+        }
+
+        result.store = Store::File {
+            records: records,
+            index: index,
+        };
+        Ok(result)
+    }
+
+    pub fn in_memory() -> Self {
+        Self {
+            store: Store::Memory(vec![]),
+            index: HashMap::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.index.values().map(|rs| rs.len()).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.index.is_empty()
+    }
+
+    // Some(None) means the position is known to have no solution within the limit.
+    // A solution is reused for larger limits and a failure for smaller limits.
+    pub fn lookup(
+        &self,
+        mode: SolveMode,
+        limit: u8,
+        board: &Board,
+        attacker: Player,
+        threat_limit: u8,
+    ) -> Option<Option<Mate>> {
+        let (symmetry, key) = Symmetry::canonical(board);
+        let (blacks, whites) = canonical_stones(board, symmetry);
+        let offsets = self.index.get(&key)?;
+        let record = offsets
+            .iter()
+            .filter_map(|&offset| self.read(offset).ok())
+            .filter(|r| r.blacks == blacks && r.whites == whites)
+            .filter(|r| r.attacker == attacker && r.mode == mode && r.threat_limit == threat_limit)
+            .find(|r| match &r.mate {
+                Some(m) => m.n_times() <= limit,
+                None => r.limit >= limit,
+            })?;
+        let inverse = symmetry.inverse();
+        Some(record.mate.as_ref().map(|m| transform(m, inverse)))
+    }
+
+    pub fn record(
+        &mut self,
+        mode: SolveMode,
+        limit: u8,
+        board: &Board,
+        attacker: Player,
+        threat_limit: u8,
+        mate: Option<&Mate>,
+    ) -> Result<(), String> {
+        let (symmetry, key) = Symmetry::canonical(board);
+        let (blacks, whites) = canonical_stones(board, symmetry);
+        let record = Record {
+            key: key,
+            blacks: blacks,
+            whites: whites,
+            attacker: attacker,
+            mode: mode,
+            limit: limit,
+            threat_limit: threat_limit,
+            mate: mate.map(|m| transform(m, symmetry)),
+        };
+        let offset = match &mut self.store {
+            Store::File { records, index } => {
+                let offset = records.seek(SeekFrom::End(0)).map_err(|e| e.to_string())?;
+                let line = format!("{}\n", record);
+                records
+                    .write_all(line.as_bytes())
+                    .map_err(|e| e.to_string())?;
+                write_index_entry(index, key, offset, offset + line.len() as u64)?;
+                offset
+            }
+            Store::Memory(records) => {
+                records.push(record);
+                records.len() as u64 - 1
+            }
+        };
+        self.index.entry(key).or_default().push(offset);
+        Ok(())
+    }
+
+    // Consults the database first, and records the result if it is new
+    pub fn solve(
+        &mut self,
+        mode: SolveMode,
+        limit: u8,
+        board: &Board,
+        attacker: Player,
+        threat_limit: u8,
+    ) -> Result<Option<Mate>, String> {
+        if let Some(known) = self.lookup(mode, limit, board, attacker, threat_limit) {
+            return Ok(known);
+        }
+        let result = try_solve(mode, limit, board, attacker, threat_limit)?;
+        self.record(mode, limit, board, attacker, threat_limit, result.as_ref())?;
+        Ok(result)
+    }
+
+    fn read(&self, offset: u64) -> Result<Record, String> {
+        match &self.store {
+            Store::File { records, .. } => {
+                let mut reader = BufReader::new(records);
+                reader
+                    .seek(SeekFrom::Start(offset))
+                    .map_err(|e| e.to_string())?;
+                let mut line = String::new();
+                reader.read_line(&mut line).map_err(|e| e.to_string())?;
+                Ok(line.parse::<Record>()?)
+            }
+            Store::Memory(records) => Ok(records[offset as usize].clone()),
+        }
+    }
+}
+
+// try_solve through the database file at the path, which is opened on every call
+pub fn try_solve_in(
+    path: &str,
+    mode: SolveMode,
+    limit: u8,
+    board: &Board,
+    attacker: Player,
+    threat_limit: u8,
+) -> Result<Option<Mate>, String> {
+    Database::open(path)?.solve(mode, limit, board, attacker, threat_limit)
+}
+
+impl fmt::Display for Record {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let attacker = if self.attacker.is_black() { 'o' } else { 'x' };
+        let (end, path) = match &self.mate {
+            None => ("none".to_string(), "-".to_string()),
+            Some(m) => {
+                let end = match m.end {
+                    End::Fours(p1, p2) => format!("fours:{},{}", p1, p2),
+                    End::Forbidden(p) => format!("forbidden:{}", p),
+                    End::Unknown => "unknown".to_string(),
+                };
+                let path = if m.path.is_empty() {
+                    "-".to_string()
+                } else {
+                    Points(m.path.clone()).to_string()
+                };
+                (end, path)
+            }
+        };
+        let stones = |ps: &[Point]| {
+            if ps.is_empty() {
+                "-".to_string()
+            } else {
+                Points(ps.to_vec()).to_string()
+            }
+        };
+        write!(
+            f,
+            "{:016x} {}/{} {} {} {} {} {} {}",
+            self.key,
+            stones(&self.blacks),
+            stones(&self.whites),
+            attacker,
+            self.mode,
+            self.limit,
+            self.threat_limit,
+            end,
+            path
+        )
+    }
+}
+
+impl FromStr for Record {
+    type Err = &'static str;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let fields: Vec<_> = s.split_whitespace().collect();
+        if fields.len() != 8 {
+            return Err("Wrong number of fields");
+        }
+        let key = u64::from_str_radix(fields[0], 16).map_err(|_| "Invalid key")?;
+        let stones = |s: &str| match s {
+            "-" => Ok(vec![]),
+            ps => ps.parse::<Points>().map(|ps| ps.0),
+        };
+        let (blacks, whites) = fields[1].split_once('/').ok_or("Invalid stones")?;
+        let (blacks, whites) = (stones(blacks)?, stones(whites)?);
+        let attacker = fields[2].parse::<Player>()?;
+        let mode = fields[3].parse::<SolveMode>()?;
+        let limit = fields[4].parse::<u8>().map_err(|_| "Invalid limit")?;
+        let threat_limit = fields[5]
+            .parse::<u8>()
+            .map_err(|_| "Invalid threat limit")?;
+        let end = match fields[6].split_once(':') {
+            None if fields[6] == "none" => None,
+            None if fields[6] == "unknown" => Some(End::Unknown),
+            Some(("fours", ps)) => match ps.parse::<Points>()?.0[..] {
+                [p1, p2] => Some(End::Fours(p1, p2)),
+                _ => return Err("Invalid end"),
+            },
+            Some(("forbidden", p)) => Some(End::Forbidden(p.parse::<Point>()?)),
+            _ => return Err("Invalid end"),
+        };
+        let path = match fields[7] {
+            "-" => vec![],
+            ps => ps.parse::<Points>()?.0,
+        };
+        Ok(Self {
+            key: key,
+            blacks: blacks,
+            whites: whites,
+            attacker: attacker,
+            mode: mode,
+            limit: limit,
+            threat_limit: threat_limit,
+            mate: end.map(|end| Mate::new(end, path)),
+        })
+    }
+}
+
+fn write_index_entry(index: &mut File, key: u64, offset: u64, next: u64) -> Result<(), String> {
+    let mut bytes = Vec::with_capacity(INDEX_ENTRY_SIZE);
+    for x in [key, offset, next] {
+        bytes.extend_from_slice(&x.to_le_bytes());
+    }
+    index.write_all(&bytes).map_err(|e| e.to_string())
+}
+
+// Stones of the board mapped by the symmetry, in the order of points
+fn canonical_stones(board: &Board, symmetry: Symmetry) -> (Vec<Point>, Vec<Point>) {
+    let stones = |r: Player| {
+        let mut result: Vec<_> = board.stones(r).map(|p| symmetry.apply(p)).collect();
+        result.sort_by_key(|&p| u8::from(p));
+        result
+    };
+    (stones(Player::Black), stones(Player::White))
+}
+
+fn transform(mate: &Mate, symmetry: Symmetry) -> Mate {
+    let end = match mate.end {
+        End::Fours(p1, p2) => End::Fours(symmetry.apply(p1), symmetry.apply(p2)),
+        End::Forbidden(p) => End::Forbidden(symmetry.apply(p)),
+        End::Unknown => End::Unknown,
+    };
+    let path = mate.path.iter().map(|&p| symmetry.apply(p)).collect();
+    Mate::new(end, path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::Player::*;
+
+    #[test]
+    fn test_record() -> Result<(), String> {
+        let line = "00000000000000ff H8,H9/I8 o vct_dfpns 4 1 fours:F13,K8 F10,G9,I10";
+        let record = line.parse::<Record>()?;
+        assert_eq!(record.key, 255);
+        assert_eq!(record.whites, [Point(8, 7)]);
+        assert_eq!(record.mode, SolveMode::VCTDFPNS);
+        assert_eq!(record.to_string(), line);
+
+        let line = "00000000000000ff H8/- x vcf 10 0 none -";
+        assert_eq!(line.parse::<Record>()?.to_string(), line);
+
+        assert!("00ff -/- o vcf 10 0 fours:F13 -".parse::<Record>().is_err());
+        assert!("00ff o vcf 10 0 none -".parse::<Record>().is_err());
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_database() -> Result<(), String> {
+        let path = std::env::temp_dir().join(format!("quintet-{}.db", std::process::id()));
+        let path = path.to_str().ok_or("Invalid path")?;
+
+        let board = "H10,H9,J9,H8,I7/I11,I9,G8,J8,H7";
+        let board = Board::from_str_with(board, Notation::Alphanumeric)?;
+        let solution = "F10,G9,I10,G10,H11,H12,G12";
+        // Mirrored over the vertical center line
+        let mirrored = Symmetry(2).apply_board(&board);
+
+        let mut db = Database::open(path)?;
+        let result = db.solve(SolveMode::VCTDFPNS, 4, &board, Black, 1)?;
+        assert_eq!(Points(result.unwrap().path).to_string(), solution);
+        let result = db.solve(SolveMode::VCTDFPNS, 3, &board, Black, 1)?;
+        assert!(result.is_none());
+        assert_eq!(db.len(), 2);
+
+        // Records are found through the index file
+        let index_path = format!("{}.idx", path);
+        let index_len = std::fs::metadata(&index_path)
+            .map_err(|e| e.to_string())?
+            .len();
+        assert_eq!(index_len, 2 * INDEX_ENTRY_SIZE as u64);
+        let db = Database::open(path)?;
+        assert_eq!(db.len(), 2);
+
+        // A record of another board with the same hash is not taken
+        let (_, key) = Symmetry::canonical(&board);
+        let line = format!("{:016x} H8/- o vct_dfpns 4 2 unknown -\n", key);
+        let file = OpenOptions::new().append(true).open(path);
+        let mut file = file.map_err(|e| e.to_string())?;
+        file.write_all(line.as_bytes()).map_err(|e| e.to_string())?;
+        let db = Database::open(path)?;
+        assert_eq!(db.len(), 3);
+        assert_eq!(db.lookup(SolveMode::VCTDFPNS, 4, &board, Black, 2), None);
+
+        // A broken index file is rebuilt
+        std::fs::write(&index_path, [0; 5]).map_err(|e| e.to_string())?;
+        let db = Database::open(path)?;
+        assert_eq!(db.len(), 3);
+        let result = try_solve_in(path, SolveMode::VCTDFPNS, 4, &board, Black, 1)?;
+        assert_eq!(Points(result.unwrap().path).to_string(), solution);
+        std::fs::remove_file(path).map_err(|e| e.to_string())?;
+        std::fs::remove_file(&index_path).map_err(|e| e.to_string())?;
+
+        let result = db.lookup(SolveMode::VCTDFPNS, 5, &mirrored, Black, 1);
+        let path = result.flatten().map(|m| Points(m.path).to_string());
+        assert_eq!(path.as_deref(), Some("J10,I9,G10,I10,H11,H12,I12"));
+
+        let result = db.lookup(SolveMode::VCTDFPNS, 2, &mirrored, Black, 1);
+        assert_eq!(result, Some(None));
+        assert_eq!(db.lookup(SolveMode::VCTDFPNS, 4, &board, White, 1), None);
+
+        Ok(())
+    }
+}
@@ -0,0 +1,415 @@
+//! C ABI for native applications.
+//!
+//! Handles returned by `quintet_*_new` / `quintet_solve` are owned by the caller
+//! and must be released with the matching `quintet_*_free` function.
+//! Pointer arguments must be valid for the documented length or null where allowed.
+#![allow(clippy::missing_safety_doc)]
+
+use crate::board::*;
+use crate::mate;
+use crate::mate::End;
+use std::convert::TryFrom;
+use std::ffi::{CStr, CString};
+use std::os::raw::c_char;
+use std::ptr;
+use std::slice;
+
+pub struct QuintetBoard {
+    board: Board,
+}
+
+#[repr(C)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum QuintetStatus {
+    Found = 0,
+    NotFound = 1,
+    Exhausted = 2,
+    Error = 3,
+}
+
+#[repr(C)]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum QuintetEndKind {
+    None = 0,
+    Fours = 1,
+    Forbidden = 2,
+    Unknown = 3,
+}
+
+#[repr(C)]
+#[derive(Debug, Clone, Copy)]
+pub struct QuintetSolveOptions {
+    pub mode: u8,
+    pub limit: u8,
+    // Nonzero for black, as a C bool may hold any byte
+    pub black: u8,
+    pub threat_limit: u8,
+    // 0 means unlimited; other values are an error except for the df-pn VCT mode
+    pub max_nodes: u64,
+}
+
+#[repr(C)]
+pub struct QuintetSolution {
+    pub status: QuintetStatus,
+    pub end_kind: QuintetEndKind,
+    pub end_points: [u8; 2],
+    pub n_end_points: u8,
+    pub path: *mut u8,
+    pub path_len: usize,
+    pub error: *mut c_char,
+}
+
+#[no_mangle]
+pub extern "C" fn quintet_board_new() -> *mut QuintetBoard {
+    into_handle(Board::new())
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn quintet_board_from_stones(
+    blacks: *const u8,
+    n_blacks: usize,
+    whites: *const u8,
+    n_whites: usize,
+) -> *mut QuintetBoard {
+    let blacks = Points::try_from(as_slice(blacks, n_blacks));
+    let whites = Points::try_from(as_slice(whites, n_whites));
+    match (blacks, whites) {
+        (Ok(blacks), Ok(whites)) => into_handle(Board::from_stones(&blacks, &whites)),
+        _ => ptr::null_mut(),
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn quintet_board_from_str(
+    s: *const c_char,
+    notation: u8,
+) -> *mut QuintetBoard {
+    if s.is_null() {
+        return ptr::null_mut();
+    }
+    let s = match CStr::from_ptr(s).to_str() {
+        Ok(s) => s,
+        Err(_) => return ptr::null_mut(),
+    };
+    let board = Notation::try_from(notation).and_then(|n| Board::from_str_with(s, n));
+    match board {
+        Ok(board) => into_handle(board),
+        Err(_) => ptr::null_mut(),
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn quintet_board_free(board: *mut QuintetBoard) {
+    if !board.is_null() {
+        drop(Box::from_raw(board));
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn quintet_board_put(
+    board: *mut QuintetBoard,
+    black: bool,
+    code: u8,
+) -> bool {
+    match (board.as_mut(), Point::try_from(code)) {
+        (Some(b), Ok(p)) => {
+            b.board.put_mut(Player::from(black), p);
+            true
+        }
+        _ => false,
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn quintet_board_remove(board: *mut QuintetBoard, code: u8) -> bool {
+    match (board.as_mut(), Point::try_from(code)) {
+        (Some(b), Ok(p)) => {
+            b.board.remove_mut(p);
+            true
+        }
+        _ => false,
+    }
+}
+
+// Returns 0 if not forbidden, otherwise 1: double three, 2: double four, 3: overline
+#[no_mangle]
+pub unsafe extern "C" fn quintet_board_forbidden(board: *const QuintetBoard, code: u8) -> u8 {
+    match (board.as_ref(), Point::try_from(code)) {
+        (Some(b), Ok(p)) => b.board.forbidden_strict(p).map_or(0, u8::from),
+        _ => 0,
+    }
+}
+
+// Writes at most `cap` codes into `out` and returns the total number of forbidden points
+#[no_mangle]
+pub unsafe extern "C" fn quintet_board_forbiddens(
+    board: *const QuintetBoard,
+    out: *mut u8,
+    cap: usize,
+) -> usize {
+    let b = match board.as_ref() {
+        Some(b) => b,
+        None => return 0,
+    };
+    let codes: Vec<u8> = b
+        .board
+        .forbiddens()
+        .into_iter()
+        .map(|(_, p)| p.into())
+        .collect();
+    if !out.is_null() {
+        let n = codes.len().min(cap);
+        ptr::copy_nonoverlapping(codes.as_ptr(), out, n);
+    }
+    codes.len()
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn quintet_board_to_string(board: *const QuintetBoard) -> *mut c_char {
+    match board.as_ref() {
+        Some(b) => into_c_string(b.board.to_pretty_string()),
+        None => ptr::null_mut(),
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn quintet_string_free(s: *mut c_char) {
+    if !s.is_null() {
+        drop(CString::from_raw(s));
+    }
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn quintet_solve(
+    board: *const QuintetBoard,
+    options: *const QuintetSolveOptions,
+) -> *mut QuintetSolution {
+    let result = match (board.as_ref(), options.as_ref()) {
+        (Some(b), Some(o)) => solve(&b.board, o),
+        _ => Err("Null argument"),
+    };
+    Box::into_raw(Box::new(QuintetSolution::new(result)))
+}
+
+#[no_mangle]
+pub unsafe extern "C" fn quintet_solution_free(solution: *mut QuintetSolution) {
+    if solution.is_null() {
+        return;
+    }
+    let solution = Box::from_raw(solution);
+    if !solution.path.is_null() {
+        let path = ptr::slice_from_raw_parts_mut(solution.path, solution.path_len);
+        drop(Box::from_raw(path));
+    }
+    quintet_string_free(solution.error);
+}
+
+// Ok(None) means the search was stopped by the node budget
+fn solve(
+    board: &Board,
+    options: &QuintetSolveOptions,
+) -> Result<Option<Option<mate::Mate>>, &'static str> {
+    let mode = mate::SolveMode::try_from(options.mode)?;
+    let player = Player::from(options.black != 0);
+    if options.max_nodes == 0 {
+        let result = mate::try_solve(mode, options.limit, board, player, options.threat_limit)?;
+        return Ok(Some(result));
+    }
+    solve_with_budget(mode, board, player, options)
+}
+
+#[cfg(feature = "vct")]
+fn solve_with_budget(
+    mode: mate::SolveMode,
+    board: &Board,
+    player: Player,
+    options: &QuintetSolveOptions,
+) -> Result<Option<Option<mate::Mate>>, &'static str> {
+    if mode != mate::SolveMode::VCTDFPNS {
+        return Err("Node budget is supported only by vct_dfpns");
+    }
+    let mut solver = mate::StepSolver::init(board, player, options.limit, options.threat_limit);
+    let max_nodes = usize::try_from(options.max_nodes).unwrap_or(usize::MAX);
+    match solver.step(max_nodes) {
+        mate::Step::Found(m) => Ok(Some(Some(m))),
+        mate::Step::NotFound => Ok(Some(None)),
+        mate::Step::Running => Ok(None),
+    }
+}
+
+#[cfg(not(feature = "vct"))]
+fn solve_with_budget(
+    _mode: mate::SolveMode,
+    _board: &Board,
+    _player: Player,
+    _options: &QuintetSolveOptions,
+) -> Result<Option<Option<mate::Mate>>, &'static str> {
+    Err("Node budget is supported only by vct_dfpns")
+}
+
+impl QuintetSolution {
+    fn new(result: Result<Option<Option<mate::Mate>>, &str>) -> Self {
+        let mut solution = Self {
+            status: QuintetStatus::NotFound,
+            end_kind: QuintetEndKind::None,
+            end_points: [0, 0],
+            n_end_points: 0,
+            path: ptr::null_mut(),
+            path_len: 0,
+            error: ptr::null_mut(),
+        };
+        let m = match result {
+            Ok(Some(Some(m))) => m,
+            Ok(Some(None)) => return solution,
+            Ok(None) => {
+                solution.status = QuintetStatus::Exhausted;
+                return solution;
+            }
+            Err(e) => {
+                solution.status = QuintetStatus::Error;
+                solution.error = into_c_string(e.to_string());
+                return solution;
+            }
+        };
+        solution.status = QuintetStatus::Found;
+        match m.end {
+            End::Fours(p1, p2) => {
+                solution.end_kind = QuintetEndKind::Fours;
+                solution.end_points = [p1.into(), p2.into()];
+                solution.n_end_points = 2;
+            }
+            End::Forbidden(p) => {
+                solution.end_kind = QuintetEndKind::Forbidden;
+                solution.end_points = [p.into(), 0];
+                solution.n_end_points = 1;
+            }
+            End::Unknown => solution.end_kind = QuintetEndKind::Unknown,
+        }
+        let path = <Vec<u8>>::from(Points(m.path)).into_boxed_slice();
+        solution.path_len = path.len();
+        solution.path = Box::into_raw(path) as *mut u8;
+        solution
+    }
+}
+
+fn into_handle(board: Board) -> *mut QuintetBoard {
+    Box::into_raw(Box::new(QuintetBoard { board: board }))
+}
+
+fn into_c_string(s: String) -> *mut c_char {
+    CString::new(s).map_or(ptr::null_mut(), CString::into_raw)
+}
+
+unsafe fn as_slice<'a>(data: *const u8, len: usize) -> &'a [u8] {
+    if data.is_null() || len == 0 {
+        &[]
+    } else {
+        slice::from_raw_parts(data, len)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_board() {
+        let s = CString::new("H9,G8,I8,H7/").unwrap();
+        unsafe {
+            let board = quintet_board_from_str(s.as_ptr(), 0);
+            assert!(!board.is_null());
+
+            let center = u8::from(Point(7, 7));
+            assert_eq!(quintet_board_forbidden(board, center), 1);
+
+            let mut out = [0u8; 4];
+            let n = quintet_board_forbiddens(board, out.as_mut_ptr(), out.len());
+            assert_eq!((n, out[0]), (1, center));
+
+            assert!(quintet_board_remove(board, u8::from(Point(7, 6))));
+            assert_eq!(quintet_board_forbiddens(board, ptr::null_mut(), 0), 0);
+            assert!(!quintet_board_put(board, true, 225));
+
+            quintet_board_free(board);
+        }
+
+        let invalid = [225u8];
+        let board = unsafe { quintet_board_from_stones(invalid.as_ptr(), 1, ptr::null(), 0) };
+        assert!(board.is_null());
+    }
+
+    #[test]
+    fn test_solve() {
+        let blacks = <Vec<u8>>::from("H10,H9,J9,H8,I7".parse::<Points>().unwrap());
+        let whites = <Vec<u8>>::from("I11,I9,G8,J8,H7".parse::<Points>().unwrap());
+        let mut options = QuintetSolveOptions {
+            mode: 0,
+            limit: 4,
+            black: 1,
+            threat_limit: 1,
+            max_nodes: 0,
+        };
+        unsafe {
+            let board = quintet_board_from_stones(
+                blacks.as_ptr(),
+                blacks.len(),
+                whites.as_ptr(),
+                whites.len(),
+            );
+
+            let solution = quintet_solve(board, &options);
+            assert_eq!((*solution).status, QuintetStatus::NotFound);
+            quintet_solution_free(solution);
+
+            options.mode = 99;
+            let solution = quintet_solve(board, &options);
+            assert_eq!((*solution).status, QuintetStatus::Error);
+            let error = CStr::from_ptr((*solution).error).to_str().unwrap();
+            assert_eq!(error, "Unknown solve mode");
+            quintet_solution_free(solution);
+
+            options.mode = 0;
+            options.max_nodes = 1;
+            let solution = quintet_solve(board, &options);
+            assert_eq!((*solution).status, QuintetStatus::Error);
+            let error = CStr::from_ptr((*solution).error).to_str().unwrap();
+            assert_eq!(error, "Node budget is supported only by vct_dfpns");
+            quintet_solution_free(solution);
+            options.max_nodes = 0;
+
+            #[cfg(feature = "vct")]
+            {
+                options.mode = 16;
+                options.black = 2;
+                let solution = quintet_solve(board, &options);
+                assert_eq!((*solution).status, QuintetStatus::Found);
+                assert_eq!((*solution).end_kind, QuintetEndKind::Fours);
+                let path = slice::from_raw_parts((*solution).path, (*solution).path_len);
+                let path = Points::try_from(path).unwrap().to_string();
+                assert_eq!(path, "F10,G9,I10,G10,H11,H12,G12");
+                quintet_solution_free(solution);
+
+                options.max_nodes = 1;
+                let solution = quintet_solve(board, &options);
+                assert_eq!((*solution).status, QuintetStatus::Exhausted);
+                quintet_solution_free(solution);
+            }
+
+            quintet_board_free(board);
+        }
+    }
+    #[test]
+    fn test_header() -> Result<(), String> {
+        // The header is checked against cbindgen, which must be installed to test ffi
+        let output = std::process::Command::new("cbindgen")
+            .args(["--config", "cbindgen.toml", "--crate", "quintet", "--quiet"])
+            .current_dir(env!("CARGO_MANIFEST_DIR"))
+            .output()
+            .map_err(|e| format!("Failed to run cbindgen: {}", e))?;
+        assert!(output.status.success());
+        let generated = String::from_utf8(output.stdout).map_err(|e| e.to_string())?;
+        let header = include_str!("../include/quintet.h");
+        assert_eq!(generated, header, "Run `cbindgen -o include/quintet.h`");
+        Ok(())
+    }
+}
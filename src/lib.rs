@@ -1,6 +1,14 @@
 pub mod analysis;
+#[cfg(feature = "batch")]
+pub mod batch;
 pub mod board;
+#[cfg(feature = "db")]
+pub mod db;
+#[cfg(feature = "ffi")]
+pub mod ffi;
 pub mod mate;
+#[cfg(feature = "wasm")]
 pub mod wasm;
 
+#[cfg(feature = "wasm")]
 pub use wasm::{decode_x, decode_y, encode_xy, solve_vcf};
@@ -1,11 +1,34 @@
 mod game;
 mod mate;
+#[cfg(feature = "vct")]
+mod session;
+#[cfg(feature = "vct")]
+mod snapshot;
 mod solve;
 mod state;
+#[cfg(feature = "vct")]
+mod step;
+mod table;
+#[cfg(feature = "vct")]
+mod tss;
 mod vcf;
+#[cfg(feature = "vct")]
 mod vct;
+#[cfg(feature = "vct-lazy")]
 mod vct_lazy;
 
 pub use game::End;
 pub use mate::Mate;
-pub use solve::{solve, SolveMode};
+#[cfg(feature = "vct")]
+pub use session::Session;
+#[cfg(feature = "vct")]
+pub use snapshot::Snapshot;
+pub use solve::{
+    solve, try_solve, try_solve_bounded, try_solve_stats, SolveMode, DEFAULT_LAMBDA_ORDER,
+    DEFAULT_THREAT_LIMIT,
+};
+#[cfg(feature = "vct")]
+pub use solve::{threat_defences, try_solve_lambda, try_solve_with};
+#[cfg(feature = "vct")]
+pub use step::{Step, StepSolver};
+pub use table::Stats;
@@ -3,6 +3,7 @@ use crate::board::*;
 use std::fmt;
 
 #[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum End {
     Fours(Point, Point),
     Forbidden(Point),
@@ -67,6 +68,7 @@ impl Game {
         }
     }
 
+    #[cfg(any(feature = "vct", feature = "vct-lazy"))]
     pub fn into_play<F, T>(&mut self, next_move: Option<Point>, mut f: F) -> T
     where
         F: FnMut(&mut Self) -> T,
@@ -81,6 +83,7 @@ impl Game {
         &self.board
     }
 
+    #[cfg(any(feature = "vct", feature = "vct-lazy"))]
     pub fn zobrist_hash(&self, n: u8) -> u64 {
         self.board.zobrist_hash_n(n)
     }
@@ -1,7 +1,8 @@
 use super::game::*;
 use crate::board::*;
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub struct Mate {
     pub end: End,
     pub path: Vec<Point>,
@@ -0,0 +1,173 @@
+use super::mate::*;
+use super::solve::*;
+use super::state::State;
+use super::table::Stats;
+use super::vcf::*;
+use super::vct::*;
+use crate::board::*;
+
+// Solver state of one attacker kept over positions of a game.
+// Tables are keyed by exact positions, so entries stay valid after moves and undos,
+// and those reachable from the new position are reused without search.
+pub struct Session {
+    pub attacker: Player,
+    pub threat_limit: u8,
+    vcf_solver: DFSSolver,
+    vct_solver: DFPNSVCTSolver,
+}
+
+impl Session {
+    pub fn new(attacker: Player, threat_limit: u8) -> Self {
+        Self {
+            attacker: attacker,
+            threat_limit: threat_limit,
+            vcf_solver: DFSSolver::init(),
+            vct_solver: DFPNSVCTSolver::init(threat_limit, 2),
+        }
+    }
+
+    // Keeps tables within table_bytes, evicting the least valuable entries when full,
+    // so that a session kept over a whole game does not grow without limit
+    pub fn bounded(attacker: Player, threat_limit: u8, table_bytes: usize) -> Self {
+        Self {
+            attacker: attacker,
+            threat_limit: threat_limit,
+            vcf_solver: DFSSolver::init_bounded(table_bytes / 4),
+            vct_solver: DFPNSVCTSolver::init_bounded(threat_limit, 2, table_bytes / 4 * 3),
+        }
+    }
+
+    // Only VCFDFS and VCTDFPNS are supported, as in try_solve_with
+    pub fn supports(mode: SolveMode) -> bool {
+        matches!(mode, VCFDFS | VCTDFPNS)
+    }
+
+    pub fn try_solve(
+        &mut self,
+        mode: SolveMode,
+        limit: u8,
+        board: &Board,
+    ) -> Result<Option<Mate>, &'static str> {
+        self.check(mode)?;
+        if let Some(mate) = validate(board, self.attacker)? {
+            return Ok(Some(mate));
+        }
+        let state = &mut VCTState::init(board, self.attacker, limit);
+        Ok(self.solve_state(mode, state))
+    }
+
+    // Mate of the attacker if the opponent to move passes, which is the threat against the opponent
+    pub fn try_solve_threat(
+        &mut self,
+        mode: SolveMode,
+        limit: u8,
+        board: &Board,
+    ) -> Result<Option<Mate>, &'static str> {
+        self.check(mode)?;
+        if let Some(mate) = validate(board, self.attacker)? {
+            return Ok(Some(mate));
+        }
+        let state = &mut VCTState::init_defending(board, self.attacker, limit);
+        state.play(None);
+        Ok(self.solve_state(mode, state))
+    }
+
+    // VCT of the attacker against every move of the opponent to move, which starts with the opponent's move
+    pub fn try_solve_defended(
+        &mut self,
+        limit: u8,
+        board: &Board,
+    ) -> Result<Option<Mate>, &'static str> {
+        self.check(VCTDFPNS)?;
+        validate(board, self.attacker)?;
+        let state = &mut VCTState::init_defending(board, self.attacker, limit);
+        Ok(self.vct_solver.solve_defended(state))
+    }
+
+    fn check(&self, mode: SolveMode) -> Result<(), &'static str> {
+        mode.check_enabled()?;
+        if !Self::supports(mode) {
+            return Err("Unsupported solve mode");
+        }
+        Ok(())
+    }
+
+    fn solve_state(&mut self, mode: SolveMode, state: &mut VCTState) -> Option<Mate> {
+        match mode {
+            VCFDFS => {
+                let state = &mut state.vcf_state(state.limit);
+                self.vcf_solver.solve(state)
+            }
+            VCTDFPNS => self.vct_solver.solve(state),
+            _ => unreachable!(),
+        }
+    }
+
+    // Drops nodes which are not solved yet, keeping proofs, disproofs and deadends
+    pub fn prune(&mut self) {
+        self.vct_solver.prune();
+    }
+
+    pub fn stats(&self) -> Stats {
+        self.vcf_solver.stats().merge(self.vct_solver.stats())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::Player::*;
+
+    #[test]
+    fn test_session() -> Result<(), String> {
+        // No. 02 from 5-moves-to-end problems by Hiroshi Okabe
+        let board = "H10,H9,J9,H8,I7/I11,I9,G8,J8,H7";
+        let board = Board::from_str_with(board, Notation::Alphanumeric)?;
+        let solution = "F10,G9,I10,G10,H11,H12,G12";
+        let mut session = Session::new(Black, 1);
+
+        let result = session.try_solve(VCTDFPNS, 4, &board)?;
+        assert_eq!(
+            result.map(|m| Points(m.path).to_string()).as_deref(),
+            Some(solution)
+        );
+        let inserts = session.stats().inserts;
+
+        // The proof holds for larger limits
+        let result = session.try_solve(VCTDFPNS, 5, &board)?;
+        assert_eq!(
+            result.map(|m| Points(m.path).to_string()).as_deref(),
+            Some(solution)
+        );
+        assert!(session.stats().inserts - inserts < 10);
+
+        assert_eq!(session.try_solve(VCTDFPNS, 3, &board)?, None);
+        let inserts = session.stats().inserts;
+        // The disproof holds for smaller limits
+        assert_eq!(session.try_solve(VCTDFPNS, 2, &board)?, None);
+        assert_eq!(session.stats().inserts, inserts);
+
+        // The threat against white to move is the same mate after a pass
+        let result = session.try_solve_threat(VCTDFPNS, 4, &board)?;
+        assert_eq!(
+            result.map(|m| Points(m.path).to_string()).as_deref(),
+            Some(solution)
+        );
+
+        // but white to move stops it
+        assert_eq!(session.try_solve_defended(4, &board)?, None);
+
+        let mut session = Session::bounded(Black, 1, 1 << 20);
+        let result = session.try_solve(VCTDFPNS, 4, &board)?;
+        assert_eq!(
+            result.map(|m| Points(m.path).to_string()).as_deref(),
+            Some(solution)
+        );
+        assert!(session.stats().capacity.is_some());
+
+        assert!(!Session::supports(VCTPNS));
+        assert!(session.try_solve(VCTPNS, 4, &board).is_err());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,208 @@
+use super::vct::{Entry, Node};
+use crate::board::*;
+use std::collections::HashMap;
+use std::convert::TryInto;
+use std::fs;
+
+/*
+Binary format (little endian):
+  magic "QNTS", version u16, hash scheme u64, attacker u8, threat limit u8,
+  then attacker table, defender table, attacker deadends and defender deadends,
+  each of which is a u64 count followed by entries.
+  A table entry is key u64, pn u32, dn u32, node limit u8, searched limit u8,
+  and a deadend entry is key u64, limit u8. Keys are zobrist hashes of boards regardless of limits.
+*/
+
+const MAGIC: &[u8; 4] = b"QNTS";
+const VERSION: u16 = 2;
+
+// Solved nodes and VCF deadends of searches by the same attacker and threat limit
+pub struct Snapshot {
+    pub attacker: Player,
+    pub threat_limit: u8,
+    attacker_table: HashMap<u64, Entry>,
+    defender_table: HashMap<u64, Entry>,
+    attacker_deadends: HashMap<u64, u8>,
+    defender_deadends: HashMap<u64, u8>,
+}
+
+impl Snapshot {
+    pub fn new(attacker: Player, threat_limit: u8) -> Self {
+        Self {
+            attacker: attacker,
+            threat_limit: threat_limit,
+            attacker_table: HashMap::new(),
+            defender_table: HashMap::new(),
+            attacker_deadends: HashMap::new(),
+            defender_deadends: HashMap::new(),
+        }
+    }
+
+    pub fn len(&self) -> usize {
+        self.attacker_table.len()
+            + self.defender_table.len()
+            + self.attacker_deadends.len()
+            + self.defender_deadends.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn load(path: &str) -> Result<Self, String> {
+        let bytes = fs::read(path).map_err(|e| e.to_string())?;
+        Ok(Self::from_bytes(&bytes)?)
+    }
+
+    pub fn save(&self, path: &str) -> Result<(), String> {
+        fs::write(path, self.to_bytes()).map_err(|e| e.to_string())
+    }
+
+    pub fn to_bytes(&self) -> Vec<u8> {
+        let mut result = vec![];
+        result.extend(MAGIC);
+        result.extend(VERSION.to_le_bytes());
+        result.extend(hash_scheme().to_le_bytes());
+        result.push(self.attacker.is_white() as u8);
+        result.push(self.threat_limit);
+        for table in [&self.attacker_table, &self.defender_table] {
+            result.extend((table.len() as u64).to_le_bytes());
+            for (key, entry) in sorted(table) {
+                result.extend(key.to_le_bytes());
+                result.extend(entry.node.pn.to_le_bytes());
+                result.extend(entry.node.dn.to_le_bytes());
+                result.push(entry.node.limit);
+                result.push(entry.limit);
+            }
+        }
+        for deadends in [&self.attacker_deadends, &self.defender_deadends] {
+            result.extend((deadends.len() as u64).to_le_bytes());
+            for (key, limit) in sorted(deadends) {
+                result.extend(key.to_le_bytes());
+                result.push(limit);
+            }
+        }
+        result
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self, &'static str> {
+        let mut r = Reader { bytes: bytes };
+        if r.take(4)? != MAGIC {
+            return Err("Not a snapshot");
+        }
+        if r.u16()? != VERSION {
+            return Err("Unsupported snapshot version");
+        }
+        if r.u64()? != hash_scheme() {
+            return Err("Snapshot of another hash scheme");
+        }
+        let attacker = Player::from(r.u8()? == 0);
+        let mut result = Self::new(attacker, r.u8()?);
+        for i in 0..2 {
+            let n = r.u64()?;
+            for _ in 0..n {
+                let key = r.u64()?;
+                let node = Node::new(r.u32()?, r.u32()?, r.u8()?);
+                let entry = Entry::new(node, r.u8()?);
+                let table = if i == 0 {
+                    &mut result.attacker_table
+                } else {
+                    &mut result.defender_table
+                };
+                table.insert(key, entry);
+            }
+        }
+        for i in 0..2 {
+            let n = r.u64()?;
+            for _ in 0..n {
+                let key = r.u64()?;
+                let limit = r.u8()?;
+                let deadends = if i == 0 {
+                    &mut result.attacker_deadends
+                } else {
+                    &mut result.defender_deadends
+                };
+                deadends.insert(key, limit);
+            }
+        }
+        if !r.bytes.is_empty() {
+            return Err("Trailing bytes in snapshot");
+        }
+        Ok(result)
+    }
+
+    pub(crate) fn attacker_table(&self) -> Vec<(u64, Entry)> {
+        sorted(&self.attacker_table)
+    }
+
+    pub(crate) fn defender_table(&self) -> Vec<(u64, Entry)> {
+        sorted(&self.defender_table)
+    }
+
+    pub(crate) fn attacker_deadends(&self) -> Vec<(u64, u8)> {
+        sorted(&self.attacker_deadends)
+    }
+
+    pub(crate) fn defender_deadends(&self) -> Vec<(u64, u8)> {
+        sorted(&self.defender_deadends)
+    }
+
+    pub(crate) fn store(
+        &mut self,
+        attacker_table: Vec<(u64, Entry)>,
+        defender_table: Vec<(u64, Entry)>,
+        attacker_deadends: Vec<(u64, u8)>,
+        defender_deadends: Vec<(u64, u8)>,
+    ) {
+        self.attacker_table.extend(attacker_table);
+        self.defender_table.extend(defender_table);
+        self.attacker_deadends.extend(attacker_deadends);
+        self.defender_deadends.extend(defender_deadends);
+    }
+}
+
+// Computed by the same hashing as table and deadend keys,
+// so that files stored with other keys are rejected
+fn hash_scheme() -> u64 {
+    let mut board = Board::new();
+    board.put_mut(Player::Black, Point(7, 7));
+    board.put_mut(Player::White, Point(8, 8));
+    board.zobrist_hash()
+}
+
+fn sorted<V: Copy>(map: &HashMap<u64, V>) -> Vec<(u64, V)> {
+    let mut result: Vec<_> = map.iter().map(|(&k, &v)| (k, v)).collect();
+    result.sort_unstable_by_key(|&(k, _)| k);
+    result
+}
+
+struct Reader<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, n: usize) -> Result<&'a [u8], &'static str> {
+        if self.bytes.len() < n {
+            return Err("Unexpected end of snapshot");
+        }
+        let (head, tail) = self.bytes.split_at(n);
+        self.bytes = tail;
+        Ok(head)
+    }
+
+    fn u8(&mut self) -> Result<u8, &'static str> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16, &'static str> {
+        Ok(u16::from_le_bytes(self.take(2)?.try_into().unwrap()))
+    }
+
+    fn u32(&mut self) -> Result<u32, &'static str> {
+        Ok(u32::from_le_bytes(self.take(4)?.try_into().unwrap()))
+    }
+
+    fn u64(&mut self) -> Result<u64, &'static str> {
+        Ok(u64::from_le_bytes(self.take(8)?.try_into().unwrap()))
+    }
+}
@@ -1,41 +1,102 @@
 use super::game::*;
 use super::mate::*;
+#[cfg(feature = "vct")]
+use super::snapshot::Snapshot;
+#[cfg(feature = "vct")]
+use super::state::State;
+use super::table::Stats;
 use super::vcf::*;
+#[cfg(feature = "vct")]
 use super::vct::*;
+#[cfg(feature = "vct-lazy")]
 use super::vct_lazy::*;
 use crate::board::Player::*;
 use crate::board::StructureKind::*;
 use crate::board::*;
 use std::convert::TryFrom;
+use std::fmt;
 use std::str::FromStr;
+#[cfg(feature = "vct")]
+use std::thread;
+
+// Order of threats of vct_lambda if not given, where moves of the third order are followed by a VCT
+pub const DEFAULT_LAMBDA_ORDER: u8 = 3;
+
+// Threat limit of the command line tool and batch files if not given
+pub const DEFAULT_THREAT_LIMIT: u8 = 3;
 
 #[derive(Debug, PartialEq, Eq, Clone, Copy)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
 pub enum SolveMode {
+    #[cfg_attr(feature = "serde", serde(rename = "vcf"))]
     VCFDFS,
+    #[cfg_attr(feature = "serde", serde(rename = "vcf_iddfs"))]
     VCFIDDFS,
+    #[cfg_attr(feature = "serde", serde(rename = "vct"))]
     VCTDFS,
+    #[cfg_attr(feature = "serde", serde(rename = "vct_iddfs"))]
     VCTIDDFS,
+    #[cfg_attr(feature = "serde", serde(rename = "vct_tss"))]
+    VCTTSS,
+    #[cfg_attr(feature = "serde", serde(rename = "vct_lambda"))]
+    VCTLAMBDA,
+    #[cfg_attr(feature = "serde", serde(rename = "vct_pns"))]
     VCTPNS,
+    #[cfg_attr(feature = "serde", serde(rename = "vct_dfpns"))]
     VCTDFPNS,
+    #[cfg_attr(feature = "serde", serde(rename = "vct_pdfpns"))]
+    VCTPDFPNS,
+    #[cfg_attr(feature = "serde", serde(rename = "vct_dfwpns"))]
+    VCTDFWPNS,
+    #[cfg_attr(feature = "serde", serde(rename = "vct_dfpnp"))]
+    VCTDFPNP,
+    #[cfg_attr(feature = "serde", serde(rename = "vct_lazy"))]
     VCTLAZY,
 }
 
 pub use SolveMode::*;
 
+impl SolveMode {
+    pub fn enabled(&self) -> bool {
+        match self {
+            VCTDFS | VCTIDDFS | VCTTSS | VCTLAMBDA | VCTPNS | VCTDFPNS | VCTPDFPNS | VCTDFWPNS
+            | VCTDFPNP => {
+                cfg!(feature = "vct")
+            }
+            VCTLAZY => cfg!(feature = "vct-lazy"),
+            _ => true,
+        }
+    }
+
+    pub(crate) fn check_enabled(self) -> Result<Self, &'static str> {
+        if self.enabled() {
+            Ok(self)
+        } else {
+            Err("Solve mode disabled by cargo features")
+        }
+    }
+}
+
 impl TryFrom<u8> for SolveMode {
     type Error = &'static str;
 
     fn try_from(value: u8) -> Result<Self, Self::Error> {
-        match value {
+        let mode = match value {
             0 => Ok(VCFDFS),
             1 => Ok(VCFIDDFS),
             10 => Ok(VCTDFS),
             11 => Ok(VCTIDDFS),
+            12 => Ok(VCTTSS),
+            13 => Ok(VCTLAMBDA),
             15 => Ok(VCTPNS),
             16 => Ok(VCTDFPNS),
+            17 => Ok(VCTPDFPNS),
+            18 => Ok(VCTDFWPNS),
+            19 => Ok(VCTDFPNP),
             20 => Ok(VCTLAZY),
             _ => Err("Unknown solve mode"),
-        }
+        };
+        mode.and_then(Self::check_enabled)
     }
 }
 
@@ -43,16 +104,42 @@ impl FromStr for SolveMode {
     type Err = &'static str;
 
     fn from_str(s: &str) -> Result<Self, Self::Err> {
-        match s {
+        let mode = match s {
             "vcf" => Ok(VCFDFS),
             "vcf_iddfs" => Ok(VCFIDDFS),
             "vct" => Ok(VCTDFS),
             "vct_iddfs" => Ok(VCTIDDFS),
+            "vct_tss" => Ok(VCTTSS),
+            "vct_lambda" => Ok(VCTLAMBDA),
             "vct_pns" => Ok(VCTPNS),
             "vct_dfpns" => Ok(VCTDFPNS),
+            "vct_pdfpns" => Ok(VCTPDFPNS),
+            "vct_dfwpns" => Ok(VCTDFWPNS),
+            "vct_dfpnp" => Ok(VCTDFPNP),
             "vct_lazy" => Ok(VCTLAZY),
             _ => Err("Unknown solve mode"),
-        }
+        };
+        mode.and_then(Self::check_enabled)
+    }
+}
+
+impl fmt::Display for SolveMode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        let s = match self {
+            VCFDFS => "vcf",
+            VCFIDDFS => "vcf_iddfs",
+            VCTDFS => "vct",
+            VCTIDDFS => "vct_iddfs",
+            VCTTSS => "vct_tss",
+            VCTLAMBDA => "vct_lambda",
+            VCTPNS => "vct_pns",
+            VCTDFPNS => "vct_dfpns",
+            VCTPDFPNS => "vct_pdfpns",
+            VCTDFWPNS => "vct_dfwpns",
+            VCTDFPNP => "vct_dfpnp",
+            VCTLAZY => "vct_lazy",
+        };
+        write!(f, "{}", s)
     }
 }
 
@@ -63,53 +150,227 @@ pub fn solve(
     attacker: Player,
     threat_limit: u8,
 ) -> Option<Mate> {
-    if let Err(e) = validate(board, attacker) {
-        return e;
+    try_solve(mode, limit, board, attacker, threat_limit).unwrap_or(None)
+}
+
+pub fn try_solve(
+    mode: SolveMode,
+    limit: u8,
+    board: &Board,
+    attacker: Player,
+    threat_limit: u8,
+) -> Result<Option<Mate>, &'static str> {
+    let (result, _) = try_solve_stats(mode, limit, board, attacker, threat_limit)?;
+    Ok(result)
+}
+
+// Also returns stats of tables and VCF deadends for modes which keep them in tables
+#[cfg_attr(not(feature = "vct"), allow(unused_variables))]
+pub fn try_solve_stats(
+    mode: SolveMode,
+    limit: u8,
+    board: &Board,
+    attacker: Player,
+    threat_limit: u8,
+) -> Result<(Option<Mate>, Option<Stats>), &'static str> {
+    mode.check_enabled()?;
+    if let Some(mate) = validate(board, attacker)? {
+        return Ok((Some(mate), None));
     }
-    match mode {
+    let result = match mode {
         VCFDFS => {
             let state = &mut VCFState::init(board, attacker, limit);
             let mut solver = DFSSolver::init();
-            solver.solve(state)
+            (solver.solve(state), Some(solver.stats()))
         }
+        #[cfg(feature = "vct")]
         VCTDFS => {
             let state = &mut VCTState::init(board, attacker, limit);
             let mut solver = DFSVCTSolver::init(threat_limit, 2);
-            solver.solve(state)
+            (solver.solve(state), None)
         }
+        #[cfg(feature = "vct")]
+        VCTTSS => {
+            let state = &mut VCTState::init(board, attacker, limit);
+            let mut solver = TSSVCTSolver::init(threat_limit, 2);
+            (solver.solve(state), None)
+        }
+        #[cfg(feature = "vct")]
+        VCTLAMBDA => {
+            let state = &mut VCTState::init(board, attacker, limit);
+            let mut solver = LambdaVCTSolver::init(DEFAULT_LAMBDA_ORDER, threat_limit, 2);
+            (solver.solve(state), None)
+        }
+        #[cfg(feature = "vct")]
         VCTPNS => {
             let state = &mut VCTState::init(board, attacker, limit);
             let mut solver = PNSVCTSolver::init(threat_limit, 2);
-            solver.solve(state)
+            (solver.solve(state), None)
         }
+        #[cfg(feature = "vct")]
         VCTDFPNS => {
             let state = &mut VCTState::init(board, attacker, limit);
             let mut solver = DFPNSVCTSolver::init(threat_limit, 2);
-            solver.solve(state)
+            (solver.solve(state), Some(solver.stats()))
+        }
+        #[cfg(feature = "vct")]
+        VCTPDFPNS => {
+            let state = &mut VCTState::init(board, attacker, limit);
+            let n_threads = thread::available_parallelism().map_or(1, |n| n.get());
+            let mut solver = ParallelDFPNSVCTSolver::init(n_threads, threat_limit, 2);
+            (solver.solve(state), Some(solver.stats()))
+        }
+        #[cfg(feature = "vct")]
+        VCTDFWPNS => {
+            let state = &mut VCTState::init(board, attacker, limit);
+            let mut solver = DFWPNSVCTSolver::init(threat_limit, 2);
+            (solver.solve(state), Some(solver.stats()))
+        }
+        #[cfg(feature = "vct")]
+        VCTDFPNP => {
+            let state = &mut VCTState::init(board, attacker, limit);
+            let mut solver = DFPNPVCTSolver::init(threat_limit, 2);
+            (solver.solve(state), Some(solver.stats()))
         }
+        #[cfg(feature = "vct-lazy")]
         VCTLAZY => {
             let state = &mut LazyVCTState::init(board, attacker, limit);
             let mut solver = LazyVCTSolver::init();
-            solver.solve(state)
+            (solver.solve(state), None)
+        }
+        _ => return Err("Unsupported solve mode"),
+    };
+    Ok(result)
+}
+
+// Lambda search up to the order, where the first order is VCF and the second is VCT
+#[cfg(feature = "vct")]
+pub fn try_solve_lambda(
+    order: u8,
+    limit: u8,
+    board: &Board,
+    attacker: Player,
+    threat_limit: u8,
+) -> Result<Option<Mate>, &'static str> {
+    if order == 0 {
+        return Err("Order of lambda search must be positive");
+    }
+    if let Some(mate) = validate(board, attacker)? {
+        return Ok(Some(mate));
+    }
+    let state = &mut VCTState::init(board, attacker, limit);
+    let mut solver = LambdaVCTSolver::init(order, threat_limit, 2);
+    Ok(solver.solve(state))
+}
+
+// Keeps tables and VCF deadends within table_bytes, and returns their stats with the result.
+// Only VCFDFS, VCTDFPNS and VCTPDFPNS are supported.
+#[cfg_attr(not(feature = "vct"), allow(unused_variables))]
+pub fn try_solve_bounded(
+    mode: SolveMode,
+    limit: u8,
+    board: &Board,
+    attacker: Player,
+    threat_limit: u8,
+    table_bytes: usize,
+) -> Result<(Option<Mate>, Stats), &'static str> {
+    mode.check_enabled()?;
+    if let Some(mate) = validate(board, attacker)? {
+        return Ok((Some(mate), Stats::default()));
+    }
+    let result = match mode {
+        VCFDFS => {
+            let state = &mut VCFState::init(board, attacker, limit);
+            let mut solver = DFSSolver::init_bounded(table_bytes);
+            (solver.solve(state), solver.stats())
+        }
+        #[cfg(feature = "vct")]
+        VCTDFPNS => {
+            let state = &mut VCTState::init(board, attacker, limit);
+            let mut solver = DFPNSVCTSolver::init_bounded(threat_limit, 2, table_bytes);
+            (solver.solve(state), solver.stats())
+        }
+        #[cfg(feature = "vct")]
+        VCTPDFPNS => {
+            let state = &mut VCTState::init(board, attacker, limit);
+            let n_threads = thread::available_parallelism().map_or(1, |n| n.get());
+            let mut solver =
+                ParallelDFPNSVCTSolver::init_bounded(n_threads, threat_limit, 2, table_bytes);
+            (solver.solve(state), solver.stats())
         }
-        _ => None,
+        _ => return Err("Unsupported solve mode"),
+    };
+    Ok(result)
+}
+
+// Starts from the proofs, disproofs and deadends in the snapshot and stores new ones into it.
+// Only VCFDFS and VCTDFPNS are supported.
+#[cfg(feature = "vct")]
+pub fn try_solve_with(
+    mode: SolveMode,
+    limit: u8,
+    board: &Board,
+    attacker: Player,
+    threat_limit: u8,
+    snapshot: &mut Snapshot,
+) -> Result<Option<Mate>, &'static str> {
+    mode.check_enabled()?;
+    if snapshot.attacker != attacker {
+        return Err("Snapshot of another attacker");
     }
+    if mode != VCFDFS && snapshot.threat_limit != threat_limit {
+        return Err("Snapshot of another threat limit");
+    }
+    if let Some(mate) = validate(board, attacker)? {
+        return Ok(Some(mate));
+    }
+    let result = match mode {
+        VCFDFS => {
+            let state = &mut VCFState::init(board, attacker, limit);
+            let mut solver = DFSSolver::init();
+            solver.load_deadends(&snapshot.attacker_deadends());
+            let result = solver.solve(state);
+            snapshot.store(vec![], vec![], solver.deadends(), vec![]);
+            result
+        }
+        VCTDFPNS => {
+            let state = &mut VCTState::init(board, attacker, limit);
+            let mut solver = DFPNSVCTSolver::init(threat_limit, 2);
+            solver.load(snapshot);
+            let result = solver.solve(state);
+            solver.save(snapshot);
+            result
+        }
+        _ => return Err("Unsupported solve mode"),
+    };
+    Ok(result)
 }
 
-fn validate(board: &Board, attacker: Player) -> Result<(), Option<Mate>> {
+// Ok(Some(_)) means the attacker already has a four and wins without search
+pub fn validate(board: &Board, attacker: Player) -> Result<Option<Mate>, &'static str> {
     if board.structures(Black, Five).next().is_some() {
-        return Err(None);
+        return Err("Black five already exists");
     }
     if board.structures(White, Five).next().is_some() {
-        return Err(None);
+        return Err("White five already exists");
     }
     if board.structures(Black, OverFive).next().is_some() {
-        return Err(None);
+        return Err("Black overline already exists");
     }
     if board.structures(attacker, Four).next().is_some() {
-        return Err(Some(Mate::new(Unknown, vec![])));
+        return Ok(Some(Mate::new(Unknown, vec![])));
     }
-    Ok(())
+    Ok(None)
+}
+
+// Moves of the defender to move which may stop the attacker's VCF played after a pass,
+// or None if the attacker has no such threat
+#[cfg(feature = "vct")]
+pub fn threat_defences(board: &Board, attacker: Player, threat_limit: u8) -> Option<Vec<Point>> {
+    let mut state = VCTState::init(board, attacker, threat_limit);
+    state.play(None);
+    let threat = DFSSolver::init().solve(&mut state.threat_state(threat_limit))?;
+    Some(state.threat_defences(&threat))
 }
 
 #[cfg(test)]
@@ -287,6 +548,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "vct")]
     fn test_vct_black() -> Result<(), String> {
         // No. 02 from 5-moves-to-end problems by Hiroshi Okabe
         let board = "
@@ -322,15 +584,49 @@ mod tests {
         let result = solve(VCTDFPNS, 4, &board, Black, 1);
         assert_eq!(path_string(result), solution);
 
-        let solution = "F10,G9,I10";
+        let result = solve(VCTPDFPNS, 4, &board, Black, 1);
+        assert_eq!(result.map(|m| m.n_times()), Some(4));
+
+        let result = solve(VCTPDFPNS, 3, &board, Black, 1);
+        assert!(result.is_none());
 
-        let result = solve(VCTLAZY, 4, &board, Black, 1);
+        let result = solve(VCTDFWPNS, 4, &board, Black, 1);
         assert_eq!(path_string(result), solution);
 
+        let result = solve(VCTDFWPNS, 3, &board, Black, 1);
+        assert!(result.is_none());
+
+        let result = solve(VCTDFPNP, 4, &board, Black, 1);
+        assert_eq!(path_string(result), solution);
+
+        let result = solve(VCTDFPNP, 3, &board, Black, 1);
+        assert!(result.is_none());
+
+        let result = solve(VCTTSS, 4, &board, Black, 1);
+        assert_eq!(path_string(result), solution);
+
+        let result = solve(VCTTSS, 3, &board, Black, 1);
+        assert!(result.is_none());
+
+        let result = solve(VCTLAMBDA, 4, &board, Black, 1);
+        assert_eq!(path_string(result), solution);
+
+        let result = solve(VCTLAMBDA, 3, &board, Black, 1);
+        assert!(result.is_none());
+
+        #[cfg(feature = "vct-lazy")]
+        {
+            let solution = "F10,G9,I10";
+
+            let result = solve(VCTLAZY, 4, &board, Black, 1);
+            assert_eq!(path_string(result), solution);
+        }
+
         Ok(())
     }
 
     #[test]
+    #[cfg(feature = "vct")]
     fn test_vct_white() -> Result<(), String> {
         let board = "
          . . . . . . . . . . . . . . .
@@ -367,15 +663,31 @@ mod tests {
         let result = solve(VCTDFPNS, 4, &board, White, 1);
         assert_eq!(path_string(result), solution);
 
-        let solution = "I10,I8,F7,E6,J11";
+        let result = solve(VCTTSS, 4, &board, White, 1);
+        assert_eq!(path_string(result), solution);
 
-        let result = solve(VCTLAZY, 4, &board, White, 1);
+        let result = solve(VCTTSS, 3, &board, White, 1);
+        assert!(result.is_none());
+
+        let result = solve(VCTLAMBDA, 4, &board, White, 1);
         assert_eq!(path_string(result), solution);
 
+        let result = solve(VCTLAMBDA, 3, &board, White, 1);
+        assert!(result.is_none());
+
+        #[cfg(feature = "vct-lazy")]
+        {
+            let solution = "I10,I8,F7,E6,J11";
+
+            let result = solve(VCTLAZY, 4, &board, White, 1);
+            assert_eq!(path_string(result), solution);
+        }
+
         Ok(())
     }
 
     #[test]
+    #[cfg(feature = "vct")]
     fn test_vct_counter() -> Result<(), String> {
         // No. 63 from 5-moves-to-end problems by Hiroshi Okabe
         let board = "
@@ -411,15 +723,25 @@ mod tests {
         let result = solve(VCTDFPNS, 4, &board, White, 1);
         assert_eq!(path_string(result), solution);
 
-        let solution = "F7,C10,E6,G8,E8,H5,E7";
+        let result = solve(VCTTSS, 4, &board, White, 1);
+        assert_eq!(path_string(result), solution);
 
-        let result = solve(VCTLAZY, 4, &board, White, 1);
+        let result = solve(VCTLAMBDA, 4, &board, White, 1);
         assert_eq!(path_string(result), solution);
 
+        #[cfg(feature = "vct-lazy")]
+        {
+            let solution = "F7,C10,E6,G8,E8,H5,E7";
+
+            let result = solve(VCTLAZY, 4, &board, White, 1);
+            assert_eq!(path_string(result), solution);
+        }
+
         Ok(())
     }
 
     #[test]
+    #[cfg(feature = "vct")]
     fn test_vct_forbidden_breaker() -> Result<(), String> {
         // No. 68 from 5-moves-to-end problems by Hiroshi Okabe
         let board = "
@@ -455,15 +777,19 @@ mod tests {
         let result = solve(VCTDFPNS, 4, &board, Black, 1);
         assert_eq!(path_string(result), solution);
 
-        let solution = "J8,I7,I8,G8,L8,K8,K7";
+        #[cfg(feature = "vct-lazy")]
+        {
+            let solution = "J8,I7,I8,G8,L8,K8,K7";
 
-        let result = solve(VCTLAZY, 4, &board, Black, 1);
-        assert_eq!(path_string(result), solution);
+            let result = solve(VCTLAZY, 4, &board, Black, 1);
+            assert_eq!(path_string(result), solution);
+        }
 
         Ok(())
     }
 
     #[test]
+    #[cfg(feature = "vct")]
     fn test_vct_fukumi_move() -> Result<(), String> {
         // https://twitter.com/nachirenju/status/1487315157382414336
         let board = "
@@ -502,15 +828,19 @@ mod tests {
         let result = solve(VCTDFPNS, 7, &board, Black, 3);
         assert_eq!(path_string(result), solution);
 
-        let solution = "G12,E10,F12,I12,H14,H13,F14,G13,F13,F11,E14,D15,G14";
+        #[cfg(feature = "vct-lazy")]
+        {
+            let solution = "G12,E10,F12,I12,H14,H13,F14,G13,F13,F11,E14,D15,G14";
 
-        let result = solve(VCTLAZY, 7, &board, Black, 3);
-        assert_eq!(path_string(result), solution);
+            let result = solve(VCTLAZY, 7, &board, Black, 3);
+            assert_eq!(path_string(result), solution);
+        }
 
         Ok(())
     }
 
     #[test]
+    #[cfg(feature = "vct")]
     fn test_vct_dual_forbiddens() -> Result<(), String> {
         let board = "
          . . . . . . . . . . . . . . .
@@ -545,14 +875,58 @@ mod tests {
         let result = solve(VCTPNS, 5, &board, White, 1);
         assert_eq!(path_string(result), solution);
 
-        let solution = "K3,G7,I3,I4,L2,J4,L3,K2,J3";
-        let result = solve(VCTLAZY, 5, &board, White, 1);
+        #[cfg(feature = "vct-lazy")]
+        {
+            let solution = "K3,G7,I3,I4,L2,J4,L3,K2,J3";
+            let result = solve(VCTLAZY, 5, &board, White, 1);
+            assert_eq!(path_string(result), solution);
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "vct")]
+    fn test_vct_lambda_order() -> Result<(), String> {
+        // J6 makes no VCF threat but threatens double threes, which λ² misses
+        let board = "
+         . . . . . . . . . . . . . . .
+         . . . . . . . . . . . . . . .
+         . . . . . . . . . . . . . . .
+         . . . . . . . . . . . . . . .
+         . . . . . . . . . . x . . . .
+         . . . . . . . . . . . . . . .
+         . . . . . x . . . . x . . . .
+         . . . . . . . . . . . . . . .
+         . . . . . . . . . . . . . . .
+         . . . . . . . . . . . . . . .
+         . . . . . x . . x . x . . . .
+         . . . . . . . . . . . . . . .
+         . . . . . . . . . . . . . . .
+         . . . . . . . . . . . . . . .
+         . . . . . . . . . . . . . . .
+        "
+        .parse::<Board>()?;
+
+        let solution = "J6,K7,J5,H5,I7,H8,J8,J4,H6";
+
+        let result = try_solve_lambda(3, 5, &board, White, 2)?;
         assert_eq!(path_string(result), solution);
 
+        let result = try_solve_lambda(3, 4, &board, White, 2)?;
+        assert!(result.is_none());
+
+        let result = try_solve_lambda(2, 5, &board, White, 2)?;
+        assert!(result.is_none());
+
+        let result = try_solve_lambda(0, 5, &board, White, 2);
+        assert!(result.is_err());
+
         Ok(())
     }
 
     #[test]
+    #[cfg(feature = "vct")]
     #[ignore]
     fn bench_vct_black() -> Result<(), String> {
         let board = "
@@ -583,6 +957,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "vct")]
     #[ignore]
     fn bench_vct_white() -> Result<(), String> {
         let board = "
@@ -614,6 +989,7 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "vct")]
     #[ignore]
     fn bench_vct_unstable() -> Result<(), String> {
         let board = "
@@ -650,6 +1026,106 @@ mod tests {
     }
 
     #[test]
+    #[cfg(feature = "vct")]
+    #[ignore]
+    fn bench_vct_dfwpns() -> Result<(), String> {
+        let cases = [
+            (BENCH_BLACK, Black, 14),
+            (BENCH_BLACK, Black, 13),
+            (BENCH_WHITE, White, 15),
+        ];
+        let nodes = compare_with_dfpns(&cases, |state| {
+            let mut solver = DFWPNSVCTSolver::init(2, 2);
+            (solver.solve(state), solver.stats().inserts)
+        })?;
+        // Only the disproof expands fewer nodes
+        assert!(nodes[1].1 < nodes[1].0);
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "vct")]
+    #[ignore]
+    fn bench_vct_dfpnp() -> Result<(), String> {
+        let cases = [
+            (BENCH_BLACK, Black, 14),
+            (BENCH_BLACK, Black, 13),
+            (BENCH_BLACK, Black, 9),
+            (BENCH_WHITE, White, 15),
+        ];
+        let nodes = compare_with_dfpns(&cases, |state| {
+            let mut solver = DFPNPVCTSolver::init(2, 2);
+            (solver.solve(state), solver.stats().inserts)
+        })?;
+        assert!(nodes.iter().all(|(dfpns, dfpnp)| dfpnp < dfpns));
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "vct")]
+    #[ignore]
+    fn bench_vct_tss() -> Result<(), String> {
+        let board = Board::from_str_with(BENCH_BLACK, Notation::Alphanumeric)?;
+        let solution = "J10,J12,L8,K9,K7,I5,L9,L7,M8,I12,K6,L6,N10,M9,N8,E4,F5,K8,N7,K10,N9";
+        let result = solve(VCTTSS, 14, &board, Black, 2);
+        assert_eq!(path_string(result), solution);
+
+        let board = Board::from_str_with(BENCH_WHITE, Notation::Alphanumeric)?;
+        let solution = "K11,K10,N12,M11,N8,H5,H6,L8,J5,J7,M5,L4,M6,K5,J4,K3,J3,J2,K4,L5,M4,L3,L2,F3,I6,E2,D1,M3,N5";
+        let result = solve(VCTTSS, 15, &board, White, 2);
+        assert_eq!(path_string(result), solution);
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "vct")]
+    #[ignore]
+    fn bench_vct_lambda() -> Result<(), String> {
+        let board = Board::from_str_with(BENCH_BLACK, Notation::Alphanumeric)?;
+        let result = try_solve_lambda(DEFAULT_LAMBDA_ORDER, 7, &board, Black, 2)?;
+        assert!(result.is_none());
+
+        let result = solve(VCTDFPNS, 7, &board, Black, 2);
+        assert!(result.is_none());
+
+        Ok(())
+    }
+
+    // Positions of bench_vct_black and bench_vct_white
+    #[cfg(feature = "vct")]
+    const BENCH_BLACK: &str = "G11,I11,J11,H9,J9,H8,H6,I6,J6/H11,H10,I10,G9,I9,I8,H7,J7,G6";
+    #[cfg(feature = "vct")]
+    const BENCH_WHITE: &str = "G11,E10,I10,J10,G9,L9,M9,F8,H8,I8,H7,I7,G6,K6,L6,D5,G4,H4/F10,G10,H10,L10,H9,I9,J9,K9,G8,J8,K8,F7,K7,E6,J6,F5,I5";
+
+    // Nodes of DFPNSVCTSolver and the given solver in each case with threat limit 2, where both must agree
+    #[cfg(feature = "vct")]
+    fn compare_with_dfpns<F>(
+        cases: &[(&str, Player, u8)],
+        mut solve_nodes: F,
+    ) -> Result<Vec<(usize, usize)>, String>
+    where
+        F: FnMut(&mut VCTState) -> (Option<Mate>, usize),
+    {
+        let mut result = vec![];
+        for &(board, attacker, limit) in cases {
+            let board = Board::from_str_with(board, Notation::Alphanumeric)?;
+            let state = &mut VCTState::init(&board, attacker, limit);
+            let mut solver = DFPNSVCTSolver::init(2, 2);
+            let expected = solver.solve(state);
+
+            let state = &mut VCTState::init(&board, attacker, limit);
+            let (actual, nodes) = solve_nodes(state);
+            assert_eq!(actual.is_some(), expected.is_some());
+            result.push((solver.stats().inserts, nodes));
+        }
+        Ok(result)
+    }
+
+    #[test]
+    #[cfg(feature = "vct")]
     #[ignore]
     fn bench_vct_small_but_long() -> Result<(), String> {
         let board = "
@@ -680,6 +1156,139 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    #[cfg(feature = "vct")]
+    fn test_vct_parallel() -> Result<(), String> {
+        let cases = [
+            // No. 02 from 5-moves-to-end problems by Hiroshi Okabe
+            ("H10,H9,J9,H8,I7/I11,I9,G8,J8,H7", Black),
+            ("G10,J10,G9,H8/H9,I9,I7", White),
+            // No. 63 from 5-moves-to-end problems by Hiroshi Okabe
+            ("J11,H10,F9,I9,F8,H8,I8,H7/I10,D9,E9,H9,J8,I7,G6", White),
+        ];
+        for (board, attacker) in cases {
+            let board = Board::from_str_with(board, Notation::Alphanumeric)?;
+            for limit in [3, 4, 5] {
+                let expected = solve(VCTDFPNS, limit, &board, attacker, 1);
+                for n_threads in [1, 4] {
+                    let state = &mut VCTState::init(&board, attacker, limit);
+                    let mut solver = ParallelDFPNSVCTSolver::init(n_threads, 1, 2);
+                    let result = solver.solve(state);
+                    if n_threads == 1 {
+                        assert_eq!(result, expected);
+                    }
+                    assert_eq!(result.is_some(), expected.is_some());
+                    if let Some(mate) = result {
+                        assert!(mate.n_times() <= limit);
+                        assert!(matches!(mate.end, Fours(_, _) | Forbidden(_)));
+                    }
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "vct")]
+    fn test_try_solve_bounded() -> Result<(), String> {
+        let board = "H10,H9,J9,H8,I7/I11,I9,G8,J8,H7";
+        let board = Board::from_str_with(board, Notation::Alphanumeric)?;
+
+        // Tables of a hundred entries keep evicting nodes but still resolve the full path
+        for mode in [VCTDFPNS, VCTPDFPNS] {
+            let (result, stats) = try_solve_bounded(mode, 4, &board, Black, 1, 1 << 12)?;
+            assert_eq!(path_string(result), "F10,G9,I10,G10,H11,H12,G12");
+            assert!(stats.evictions > 0);
+            assert!(stats.entries <= stats.capacity.unwrap());
+
+            let (result, _) = try_solve_bounded(mode, 3, &board, Black, 1, 1 << 12)?;
+            assert!(result.is_none());
+        }
+
+        let (result, stats) = try_solve_bounded(VCFDFS, 255, &board, White, 1, 1 << 12)?;
+        assert_eq!(result, solve(VCFDFS, 255, &board, White, 1));
+        assert!(stats.capacity.is_some());
+
+        let result = try_solve_bounded(VCTPNS, 4, &board, Black, 1, 1 << 12);
+        assert_eq!(result, Err("Unsupported solve mode"));
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_try_solve() -> Result<(), String> {
+        let blacks = "H8,H9,H10,H11,H12".parse::<Points>()?;
+        let board = Board::from_stones(&blacks, &Points(vec![]));
+        let result = try_solve(VCFDFS, 1, &board, White, 0);
+        assert_eq!(result, Err("Black five already exists"));
+        assert_eq!(solve(VCFDFS, 1, &board, White, 0), None);
+
+        let blacks = "H8,H9,H10,H11".parse::<Points>()?;
+        let board = Board::from_stones(&blacks, &Points(vec![]));
+        let result = try_solve(VCFDFS, 1, &board, Black, 0);
+        assert_eq!(result, Ok(Some(Mate::new(Unknown, vec![]))));
+
+        let result = try_solve(VCFIDDFS, 1, &board, White, 0);
+        assert_eq!(result, Err("Unsupported solve mode"));
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "vct")]
+    fn test_try_solve_with() -> Result<(), String> {
+        let board = "H10,H9,J9,H8,I7/I11,I9,G8,J8,H7";
+        let board = Board::from_str_with(board, Notation::Alphanumeric)?;
+        let solution = "F10,G9,I10,G10,H11,H12,G12";
+
+        let mut snapshot = Snapshot::new(Black, 1);
+        let result = try_solve_with(VCTDFPNS, 4, &board, Black, 1, &mut snapshot)?;
+        assert_eq!(path_string(result), solution);
+        assert!(!snapshot.is_empty());
+
+        let path = std::env::temp_dir().join(format!("quintet-{}.snapshot", std::process::id()));
+        let path = path.to_str().ok_or("Invalid path")?;
+        snapshot.save(path)?;
+        let mut loaded = Snapshot::load(path)?;
+        std::fs::remove_file(path).map_err(|e| e.to_string())?;
+        assert_eq!(loaded.to_bytes(), snapshot.to_bytes());
+
+        let result = try_solve_with(VCTDFPNS, 4, &board, Black, 1, &mut loaded)?;
+        assert_eq!(path_string(result), solution);
+        assert_eq!(loaded.len(), snapshot.len());
+
+        let result = try_solve_with(VCTDFPNS, 4, &board, White, 1, &mut loaded);
+        assert_eq!(result, Err("Snapshot of another attacker"));
+        let result = try_solve_with(VCTDFPNS, 4, &board, Black, 2, &mut loaded);
+        assert_eq!(result, Err("Snapshot of another threat limit"));
+
+        let bytes = snapshot.to_bytes();
+        let result = Snapshot::from_bytes(&bytes[..bytes.len() - 1]);
+        assert_eq!(result.err(), Some("Unexpected end of snapshot"));
+        let result = Snapshot::from_bytes(b"QNTX");
+        assert_eq!(result.err(), Some("Not a snapshot"));
+
+        Ok(())
+    }
+
+    #[test]
+    #[cfg(feature = "serde")]
+    fn test_serde() -> Result<(), String> {
+        let mate = Mate::new(Fours(Point(7, 9), Point(7, 10)), vec![Point(7, 8)]);
+        let json = serde_json::to_string(&mate).map_err(|e| e.to_string())?;
+        assert_eq!(json, r#"{"end":{"Fours":["H10","H11"]},"path":["H9"]}"#);
+        let result: Mate = serde_json::from_str(&json).map_err(|e| e.to_string())?;
+        assert_eq!(result, mate);
+
+        let json = serde_json::to_string(&VCTDFPNS).map_err(|e| e.to_string())?;
+        assert_eq!(json, r#""vct_dfpns""#);
+        let result: SolveMode = serde_json::from_str(&json).map_err(|e| e.to_string())?;
+        assert_eq!(result, VCTDFPNS);
+
+        Ok(())
+    }
+
     fn path_string(maybe_mate: Option<Mate>) -> String {
         maybe_mate
             .map(|m| Points(m.path).to_string())
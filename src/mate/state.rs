@@ -43,6 +43,7 @@ pub trait State {
         self.game().turn == self.attacker()
     }
 
+    #[cfg(any(feature = "vct", feature = "vct-lazy"))]
     fn zobrist_hash(&self) -> u64 {
         self.game().zobrist_hash(self.limit())
     }
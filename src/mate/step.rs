@@ -0,0 +1,172 @@
+use super::mate::*;
+use super::solve::validate;
+use super::table::Stats;
+use super::vct::*;
+use crate::board::*;
+
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub enum Step {
+    Running,
+    Found(Mate),
+    NotFound,
+}
+
+pub use Step::*;
+
+pub struct StepSolver {
+    state: VCTState,
+    solver: StepVCTSolver,
+    result: Option<Step>,
+}
+
+impl StepSolver {
+    pub fn init(board: &Board, attacker: Player, limit: u8, threat_limit: u8) -> Self {
+        Self::new(board, attacker, limit, StepVCTSolver::init(threat_limit, 2))
+    }
+
+    // Tables and VCF deadends are kept in table_bytes in total.
+    // Too small tables make steps search evicted nodes again and again.
+    pub fn init_bounded(
+        board: &Board,
+        attacker: Player,
+        limit: u8,
+        threat_limit: u8,
+        table_bytes: usize,
+    ) -> Self {
+        let solver = StepVCTSolver::init_bounded(threat_limit, 2, table_bytes);
+        Self::new(board, attacker, limit, solver)
+    }
+
+    fn new(board: &Board, attacker: Player, limit: u8, solver: StepVCTSolver) -> Self {
+        let result = match validate(board, attacker) {
+            Ok(None) => None,
+            Ok(Some(mate)) => Some(Found(mate)),
+            Err(_) => Some(NotFound),
+        };
+        Self {
+            state: VCTState::init(board, attacker, limit),
+            solver: solver,
+            result: result,
+        }
+    }
+
+    pub fn step(&mut self, max_nodes: usize) -> Step {
+        if let Some(result) = &self.result {
+            return result.clone();
+        }
+        let node = self.solver.step(&mut self.state, max_nodes);
+        let result = if node.pn == 0 {
+            match self.solver.resolve_all(&mut self.state) {
+                Some(mate) => Found(mate),
+                None => NotFound,
+            }
+        } else if node.dn == 0 {
+            NotFound
+        } else {
+            return Running;
+        };
+        self.result = Some(result.clone());
+        result
+    }
+
+    pub fn best_line(&mut self) -> Vec<Point> {
+        match &self.result {
+            Some(Found(mate)) => mate.path.clone(),
+            Some(NotFound) => vec![],
+            _ => self.solver.best_line(&mut self.state),
+        }
+    }
+
+    pub fn n_nodes(&self) -> usize {
+        self.solver.n_nodes()
+    }
+
+    pub fn stats(&self) -> Stats {
+        self.solver.stats()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::Player::*;
+
+    #[test]
+    fn test_step() -> Result<(), String> {
+        // No. 02 from 5-moves-to-end problems by Hiroshi Okabe
+        let board = "
+         . . . . . . . . . . . . . . .
+         . . . . . . . . . . . . . . .
+         . . . . . . . . . . . . . . .
+         . . . . . . . . . . . . . . .
+         . . . . . . . . x . . . . . .
+         . . . . . . . o . . . . . . .
+         . . . . . . . o x o . . . . .
+         . . . . . . x o . x . . . . .
+         . . . . . . . x o . . . . . .
+         . . . . . . . . . . . . . . .
+         . . . . . . . . . . . . . . .
+         . . . . . . . . . . . . . . .
+         . . . . . . . . . . . . . . .
+         . . . . . . . . . . . . . . .
+         . . . . . . . . . . . . . . .
+        "
+        .parse::<Board>()?;
+
+        let mut solver = StepSolver::init(&board, Black, 4, 1);
+        let mut n_steps = 0;
+        let result = loop {
+            n_steps += 1;
+            match solver.step(1) {
+                Running => continue,
+                result => break result,
+            }
+        };
+        assert!(n_steps > 1);
+        assert!(solver.n_nodes() >= n_steps - 1);
+
+        let path = match result {
+            Found(mate) => Points(mate.path).to_string(),
+            _ => String::new(),
+        };
+        assert_eq!(path, "F10,G9,I10,G10,H11,H12,G12");
+        assert_eq!(Points(solver.best_line()).to_string(), path);
+        assert_eq!(solver.step(1), solver.step(100));
+
+        let mut solver = StepSolver::init(&board, Black, 3, 1);
+        let result = loop {
+            match solver.step(10) {
+                Running => continue,
+                result => break result,
+            }
+        };
+        assert_eq!(result, NotFound);
+
+        Ok(())
+    }
+
+    #[test]
+    fn test_step_bounded() -> Result<(), String> {
+        let board = "H10,H9,J9,H8,I7/I11,I9,G8,J8,H7";
+        let board = Board::from_str_with(board, Notation::Alphanumeric)?;
+
+        let mut solver = StepSolver::init_bounded(&board, Black, 4, 1, 1 << 20);
+        let result = loop {
+            match solver.step(10) {
+                Running => continue,
+                result => break result,
+            }
+        };
+        let path = match result {
+            Found(mate) => Points(mate.path).to_string(),
+            _ => String::new(),
+        };
+        assert_eq!(path, "F10,G9,I10,G10,H11,H12,G12");
+
+        let stats = solver.stats();
+        assert!(stats.evictions > 0);
+        assert!(stats.entries <= stats.capacity.unwrap());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,242 @@
+// Entries and retention are only used by VCT solvers
+#![cfg_attr(not(feature = "vct"), allow(dead_code))]
+
+use std::collections::HashMap;
+use std::mem;
+
+const WAYS: usize = 4;
+
+// Entries of larger priority survive longer on replacement,
+// and pinned ones are never replaced by unpinned ones
+pub trait Priority: Copy {
+    fn priority(&self) -> u64;
+
+    fn pinned(&self) -> bool {
+        false
+    }
+}
+
+impl Priority for u8 {
+    fn priority(&self) -> u64 {
+        *self as u64
+    }
+}
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Stats {
+    pub entries: usize,
+    pub capacity: Option<usize>,
+    pub inserts: usize,
+    pub evictions: usize,
+}
+
+impl Stats {
+    pub fn merge(&self, another: Self) -> Self {
+        Self {
+            entries: self.entries + another.entries,
+            capacity: self.capacity.and_then(|c| another.capacity.map(|a| c + a)),
+            inserts: self.inserts + another.inserts,
+            evictions: self.evictions + another.evictions,
+        }
+    }
+}
+
+pub struct HashTable<V> {
+    storage: Storage<V>,
+    stats: Stats,
+}
+
+enum Storage<V> {
+    Unbounded(HashMap<u64, V>),
+    Bounded(Vec<[Option<(u64, V)>; WAYS]>),
+}
+
+impl<V: Priority> HashTable<V> {
+    pub fn new() -> Self {
+        Self {
+            storage: Storage::Unbounded(HashMap::new()),
+            stats: Stats::default(),
+        }
+    }
+
+    // Fixed number of buckets that fits in the given bytes
+    pub fn bounded(bytes: usize) -> Self {
+        let bucket_size = mem::size_of::<[Option<(u64, V)>; WAYS]>();
+        let n_buckets = (bytes / bucket_size).max(1);
+        // Power of two so that the lower bits of the key select a bucket
+        let n_buckets = 1 << (usize::BITS - 1 - n_buckets.leading_zeros());
+        Self {
+            storage: Storage::Bounded(vec![[None; WAYS]; n_buckets]),
+            stats: Stats {
+                capacity: Some(n_buckets * WAYS),
+                ..Stats::default()
+            },
+        }
+    }
+
+    pub fn get(&self, key: u64) -> Option<V> {
+        match &self.storage {
+            Storage::Unbounded(map) => map.get(&key).copied(),
+            Storage::Bounded(buckets) => buckets[Self::index(buckets, key)]
+                .iter()
+                .flatten()
+                .find(|(k, _)| *k == key)
+                .map(|&(_, v)| v),
+        }
+    }
+
+    pub fn insert(&mut self, key: u64, value: V) {
+        let stats = &mut self.stats;
+        let buckets = match &mut self.storage {
+            Storage::Unbounded(map) => {
+                if map.insert(key, value).is_none() {
+                    stats.inserts += 1;
+                    stats.entries += 1;
+                }
+                return;
+            }
+            Storage::Bounded(buckets) => buckets,
+        };
+        let index = Self::index(buckets, key);
+        let bucket = &mut buckets[index];
+        if let Some(slot) = bucket.iter_mut().flatten().find(|(k, _)| *k == key) {
+            slot.1 = value;
+            return;
+        }
+        stats.inserts += 1;
+        if let Some(slot) = bucket.iter_mut().find(|s| s.is_none()) {
+            slot.replace((key, value));
+            stats.entries += 1;
+            return;
+        }
+        // The new entry replaces the least valuable one even if it is more valuable.
+        // Otherwise df-pn would search a discarded child again and again with the same threshold.
+        // Only a bucket full of pinned entries discards an unpinned new one, which counts as an eviction too.
+        stats.evictions += 1;
+        let slot = bucket
+            .iter_mut()
+            .filter(|s| value.pinned() || !s.map_or(false, |(_, v)| v.pinned()))
+            .min_by_key(|s| s.map_or(0, |(_, v)| v.priority()));
+        if let Some(slot) = slot {
+            slot.replace((key, value));
+        }
+    }
+
+    pub fn stats(&self) -> Stats {
+        self.stats
+    }
+
+    pub fn entries(&self) -> Vec<(u64, V)> {
+        match &self.storage {
+            Storage::Unbounded(map) => map.iter().map(|(&k, &v)| (k, v)).collect(),
+            Storage::Bounded(buckets) => buckets.iter().flatten().flatten().copied().collect(),
+        }
+    }
+
+    pub fn retain<F: Fn(&V) -> bool>(&mut self, f: F) {
+        match &mut self.storage {
+            Storage::Unbounded(map) => map.retain(|_, v| f(v)),
+            Storage::Bounded(buckets) => {
+                for slot in buckets.iter_mut().flatten() {
+                    let keep = match slot {
+                        Some((_, v)) => f(v),
+                        None => true,
+                    };
+                    if !keep {
+                        slot.take();
+                    }
+                }
+            }
+        }
+        self.stats.entries = self.entries().len();
+    }
+
+    fn index(buckets: &[[Option<(u64, V)>; WAYS]], key: u64) -> usize {
+        key as usize & (buckets.len() - 1)
+    }
+}
+
+impl<V: Priority> Default for HashTable<V> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_bounded() {
+        let mut table = HashTable::<u8>::bounded(1);
+        assert_eq!(table.stats().capacity, Some(WAYS));
+
+        for key in 0..WAYS as u64 {
+            table.insert(key, 2);
+        }
+        assert_eq!(table.stats().entries, WAYS);
+        assert_eq!(table.get(0), Some(2));
+
+        // The least valuable one is evicted
+        table.insert(0, 3);
+        table.insert(100, 1);
+        assert_eq!(table.get(100), Some(1));
+        assert_eq!(table.get(0), Some(3));
+        assert_eq!(table.get(1), None);
+
+        table.insert(200, 2);
+        assert_eq!(table.get(100), None);
+
+        let stats = table.stats();
+        assert_eq!((stats.entries, stats.inserts, stats.evictions), (4, 6, 2));
+    }
+
+    #[derive(Clone, Copy)]
+    struct Pinned(u8);
+
+    impl Priority for Pinned {
+        fn priority(&self) -> u64 {
+            0
+        }
+
+        fn pinned(&self) -> bool {
+            self.0 > 0
+        }
+    }
+
+    #[test]
+    fn test_pinned() {
+        let mut table = HashTable::<Pinned>::bounded(1);
+        for key in 0..WAYS as u64 {
+            table.insert(key, Pinned(1));
+        }
+
+        // An unpinned one is discarded
+        table.insert(100, Pinned(0));
+        assert!(table.get(100).is_none());
+        assert!((0..WAYS as u64).all(|k| table.get(k).is_some()));
+
+        // but a pinned one replaces another
+        table.insert(200, Pinned(1));
+        assert!(table.get(200).is_some());
+
+        let stats = table.stats();
+        assert_eq!((stats.entries, stats.evictions), (WAYS, 2));
+    }
+
+    #[test]
+    fn test_unbounded() {
+        let mut table = HashTable::<u8>::new();
+        table.insert(1, 1);
+        table.insert(1, 2);
+        table.insert(2, 1);
+        assert_eq!(table.get(1), Some(2));
+        assert_eq!(table.stats().capacity, None);
+        assert_eq!((table.stats().entries, table.stats().evictions), (2, 0));
+
+        table.retain(|&v| v > 1);
+        assert_eq!(table.get(2), None);
+        assert_eq!(table.stats().entries, 1);
+    }
+}
@@ -0,0 +1,4 @@
+mod attack;
+mod space;
+
+pub use space::ThreatSpace;
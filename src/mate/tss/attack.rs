@@ -0,0 +1,110 @@
+use crate::board::*;
+
+// A threat of the attacker: the gain square is the move and cost squares are defences against it
+#[derive(Debug, PartialEq, Eq, Clone)]
+pub struct Attack {
+    pub gain: Point,
+    pub direction: Direction,
+    pub stones: Vec<Point>,
+    pub costs: Vec<Point>,
+}
+
+// Threats made by the gain move, or None if the move wins whatever the defender does
+pub fn attacks(board: &Board, r: Player, gain: Point) -> Option<Vec<Attack>> {
+    let next = board.put(r, gain);
+    let threats = next.threats_on(gain, r);
+
+    let mut four_eyes = vec![];
+    for t in threats.iter().filter(|t| t.kind.is_four()) {
+        for &e in &t.eyes {
+            if !four_eyes.contains(&e) {
+                four_eyes.push(e);
+            }
+        }
+    }
+    if four_eyes.len() >= 2
+        || four_eyes
+            .iter()
+            .any(|&e| is_forbidden(&next, r.opponent(), e))
+    {
+        return None;
+    }
+
+    let mut result = vec![];
+    for t in threats {
+        let costs = if t.kind.is_four() {
+            t.eyes.clone()
+        } else if t.real {
+            three_costs(&next, r, gain, &t)
+        } else {
+            continue;
+        };
+        if costs.is_empty() {
+            return None;
+        }
+        result.push(Attack {
+            gain: gain,
+            direction: t.direction,
+            stones: t.stones,
+            costs: costs,
+        });
+    }
+    Some(result)
+}
+
+// Moves after which the three cannot become a straight four any more
+fn three_costs(board: &Board, r: Player, gain: Point, three: &Threat) -> Vec<Point> {
+    let mut candidates = three.eyes.clone();
+    for &e in &three.eyes {
+        let next = board.put(r, e);
+        let fours = next.threats_on(e, r);
+        for t in fours.iter().filter(|t| t.direction == three.direction) {
+            for &p in &t.eyes {
+                if !candidates.contains(&p) {
+                    candidates.push(p);
+                }
+            }
+        }
+    }
+    candidates.retain(|&p| {
+        if is_forbidden(board, r.opponent(), p) {
+            return false;
+        }
+        let next = board.put(r.opponent(), p);
+        !next
+            .threats_on(gain, r)
+            .iter()
+            .any(|t| t.direction == three.direction && t.kind.is_three() && t.real)
+    });
+    candidates
+}
+
+fn is_forbidden(board: &Board, r: Player, p: Point) -> bool {
+    r.is_black() && board.forbidden(p).is_some()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_attacks() -> Result<(), String> {
+        let board = Board::from_str_with("H8,I8/H9,I9", Notation::Alphanumeric)?;
+        let gain = Point::from_str_with("J8", Notation::Alphanumeric)?;
+        let result = attacks(&board, Player::Black, gain).ok_or("Unexpected win")?;
+        assert_eq!(result.len(), 1);
+        assert_eq!(result[0].direction, Direction::Horizontal);
+        assert_eq!(Points(result[0].costs.clone()).to_string(), "G8,K8");
+
+        let board = Board::from_str_with("H8,I8,J8/H9,I9", Notation::Alphanumeric)?;
+        let gain = Point::from_str_with("K8", Notation::Alphanumeric)?;
+        assert_eq!(attacks(&board, Player::Black, gain), None);
+
+        let board = Board::from_str_with("H8,I8,J8/G8,H9,I9", Notation::Alphanumeric)?;
+        let gain = Point::from_str_with("K8", Notation::Alphanumeric)?;
+        let result = attacks(&board, Player::Black, gain).ok_or("Unexpected win")?;
+        assert_eq!(Points(result[0].costs.clone()).to_string(), "L8");
+
+        Ok(())
+    }
+}
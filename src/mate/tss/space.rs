@@ -0,0 +1,280 @@
+use super::attack::*;
+use crate::board::*;
+use std::collections::HashSet;
+
+/*
+Threat-space search and dependency-based search are proposed in the following thesis:
+
+Allis, L. Victor.
+"Searching for solutions in games and artificial intelligence."
+PhD thesis, University of Limburg (1994).
+
+The defender is assumed to occupy all cost squares of each threat at once.
+Dependency stages add threats using the last gain square of a branch,
+and combination stages put two independent branches together to find threats using both.
+*/
+
+struct Branch {
+    board: Board,
+    attacks: Vec<Attack>,
+}
+
+impl Branch {
+    fn gains(&self) -> Vec<Point> {
+        self.attacks.iter().map(|a| a.gain).collect()
+    }
+
+    // New branches by the gain move, or the winning sequence of gain squares
+    fn extend<F>(
+        &self,
+        r: Player,
+        limit: u8,
+        gain: Point,
+        depends: F,
+    ) -> Result<Vec<Branch>, Vec<Point>>
+    where
+        F: Fn(&Attack) -> bool,
+    {
+        if self.attacks.len() >= limit as usize {
+            return Ok(vec![]);
+        }
+        if r.is_black() && self.board.forbidden(gain).is_some() {
+            return Ok(vec![]);
+        }
+        let attacks = match attacks(&self.board, r, gain) {
+            Some(attacks) => attacks,
+            None => {
+                let mut gains = self.gains();
+                gains.push(gain);
+                return Err(gains);
+            }
+        };
+        if self.attacks.len() + 1 >= limit as usize {
+            return Ok(vec![]);
+        }
+        let mut result = vec![];
+        for attack in attacks.into_iter().filter(|a| depends(a)) {
+            let mut branch = Branch {
+                board: self.board.clone(),
+                attacks: self.attacks.clone(),
+            };
+            branch.apply(r, attack);
+            result.push(branch);
+        }
+        Ok(result)
+    }
+
+    fn apply(&mut self, r: Player, attack: Attack) {
+        self.board.put_mut(r, attack.gain);
+        for &c in &attack.costs {
+            self.board.put_mut(r.opponent(), c);
+        }
+        self.attacks.push(attack);
+    }
+
+    fn includes(&self, other: &Branch) -> bool {
+        let gains = self.gains();
+        other.attacks.iter().all(|a| gains.contains(&a.gain))
+    }
+
+    // Threats of the other branch are made again on this one, where their cost squares may differ.
+    // Returns None if some of them conflict with this branch.
+    fn combine(&self, r: Player, limit: u8, other: &Branch) -> Result<Option<Branch>, Vec<Point>> {
+        let mut result = Branch {
+            board: self.board.clone(),
+            attacks: self.attacks.clone(),
+        };
+        for attack in &other.attacks {
+            let gain = attack.gain;
+            if self.attacks.iter().any(|a| a.gain == gain) {
+                continue;
+            }
+            if result.attacks.len() >= limit as usize {
+                return Ok(None);
+            }
+            if result.board.stone(gain).is_some() {
+                return Ok(None);
+            }
+            if r.is_black() && result.board.forbidden(gain).is_some() {
+                return Ok(None);
+            }
+            let attacks = match attacks(&result.board, r, gain) {
+                Some(attacks) => attacks,
+                None => {
+                    let mut gains = result.gains();
+                    gains.push(gain);
+                    return Err(gains);
+                }
+            };
+            let same = attacks
+                .into_iter()
+                .find(|a| a.direction == attack.direction);
+            match same {
+                Some(same) => result.apply(r, same),
+                None => return Ok(None),
+            }
+        }
+        if result.attacks.len() + 1 >= limit as usize {
+            return Ok(None);
+        }
+        Ok(Some(result))
+    }
+}
+
+pub struct ThreatSpace {
+    attacker: Player,
+    limit: u8,
+    branches: Vec<Branch>,
+    visited: HashSet<u64>,
+    fresh: Vec<usize>,
+}
+
+impl ThreatSpace {
+    pub fn init(board: &Board, attacker: Player, limit: u8) -> Self {
+        let root = Branch {
+            board: board.clone(),
+            attacks: vec![],
+        };
+        Self {
+            attacker: attacker,
+            limit: limit,
+            branches: vec![root],
+            visited: HashSet::new(),
+            fresh: vec![0],
+        }
+    }
+
+    fn len(&self) -> usize {
+        self.branches.len()
+    }
+
+    // Runs stages until no more branches are found or there are max_branches,
+    // and returns all the winning sequences of gain squares found on the way.
+    pub fn search(&mut self, max_branches: usize) -> Vec<Vec<Point>> {
+        let mut wins = vec![];
+        while !self.fresh.is_empty() {
+            let mut stage = vec![];
+            let mut queue = std::mem::take(&mut self.fresh);
+            while let Some(i) = queue.pop() {
+                if self.len() >= max_branches {
+                    return wins;
+                }
+                let added = self.expand(i, &mut wins);
+                queue.extend(&added);
+                stage.extend(added);
+            }
+            self.fresh = self.combine(&stage, max_branches, &mut wins);
+        }
+        wins
+    }
+
+    // Dependency stage
+    fn expand(&mut self, i: usize, wins: &mut Vec<Vec<Point>>) -> Vec<usize> {
+        let branch = &self.branches[i];
+        let last = branch.attacks.last().map(|a| a.gain);
+        let candidates: Vec<_> = match last {
+            Some(q) => branch.board.neighbors(q, 4, true).collect(),
+            None => {
+                let board = &branch.board;
+                let stones = board.stones(self.attacker);
+                stones.flat_map(|s| board.neighbors(s, 4, true)).collect()
+            }
+        };
+        let mut extended = vec![];
+        let mut tried = vec![];
+        for g in candidates {
+            if tried.contains(&g) {
+                continue;
+            }
+            tried.push(g);
+            let depends = |a: &Attack| match last {
+                Some(q) => a.stones.contains(&q),
+                None => true,
+            };
+            match branch.extend(self.attacker, self.limit, g, depends) {
+                Ok(branches) => extended.extend(branches),
+                Err(gains) => wins.push(gains),
+            }
+        }
+        self.add(extended)
+    }
+
+    // Combination stage
+    fn combine(
+        &mut self,
+        stage: &[usize],
+        max_branches: usize,
+        wins: &mut Vec<Vec<Point>>,
+    ) -> Vec<usize> {
+        let mut in_stage = vec![false; self.len()];
+        for &i in stage {
+            in_stage[i] = true;
+        }
+        let mut combined = vec![];
+        for &i in stage {
+            for (j, &staged) in in_stage.iter().enumerate() {
+                if j == i || (staged && j < i) {
+                    continue;
+                }
+                let (a, b) = (&self.branches[i], &self.branches[j]);
+                let (qa, qb) = match (a.attacks.last(), b.attacks.last()) {
+                    (Some(x), Some(y)) => (x.gain, y.gain),
+                    _ => continue,
+                };
+                // A new threat using both is on lines through the last gain squares
+                if (qa.0 as i8 - qb.0 as i8).abs() > 8 || (qa.1 as i8 - qb.1 as i8).abs() > 8 {
+                    continue;
+                }
+                if a.includes(b) || b.includes(a) {
+                    continue;
+                }
+                // Cost squares depend on the order, where the latter threats are made again
+                for (x, y) in [(a, b), (b, a)] {
+                    match x.combine(self.attacker, self.limit, y) {
+                        Ok(Some(branch)) => combined.push(branch),
+                        Ok(None) => (),
+                        Err(gains) => wins.push(gains),
+                    }
+                }
+                if self.len() + combined.len() >= max_branches {
+                    return self.add(combined);
+                }
+            }
+        }
+        self.add(combined)
+    }
+
+    fn add(&mut self, branches: Vec<Branch>) -> Vec<usize> {
+        let mut result = vec![];
+        for branch in branches {
+            if self.visited.insert(branch.board.zobrist_hash()) {
+                self.branches.push(branch);
+                result.push(self.branches.len() - 1);
+            }
+        }
+        result
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_search() -> Result<(), String> {
+        // No. 02 from 5-moves-to-end problems by Hiroshi Okabe
+        let board = "H10,H9,J9,H8,I7/I11,I9,G8,J8,H7";
+        let board = Board::from_str_with(board, Notation::Alphanumeric)?;
+        let solution = "F10,I10,H11,G12".parse::<Points>()?.0;
+
+        let mut space = ThreatSpace::init(&board, Player::Black, 4);
+        let wins = space.search(1000);
+        assert!(wins.iter().any(|w| solution.iter().all(|p| w.contains(p))));
+        assert!(wins.iter().all(|w| w.len() <= 4));
+
+        let mut space = ThreatSpace::init(&board, Player::Black, 2);
+        assert!(space.search(1000).is_empty());
+
+        Ok(())
+    }
+}
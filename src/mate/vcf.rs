@@ -1,7 +1,9 @@
 mod dfs;
+#[cfg(any(feature = "vct", feature = "vct-lazy"))]
 mod iddfs;
 mod state;
 
 pub use dfs::DFSSolver;
+#[cfg(any(feature = "vct", feature = "vct-lazy"))]
 pub use iddfs::IDDFSSolver;
 pub use state::VCFState;
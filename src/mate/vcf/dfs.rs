@@ -3,16 +3,39 @@ use crate::board::*;
 use crate::mate::game::*;
 use crate::mate::mate::*;
 use crate::mate::state::State;
-use std::collections::HashSet;
+use crate::mate::table::*;
 
 pub struct DFSSolver {
-    deadends: HashSet<u64>,
+    // Values are limits, so that deeper deadends are kept in a bounded table
+    deadends: HashTable<u8>,
 }
 
 impl DFSSolver {
     pub fn init() -> Self {
         Self {
-            deadends: HashSet::new(),
+            deadends: HashTable::new(),
+        }
+    }
+
+    pub fn init_bounded(bytes: usize) -> Self {
+        Self {
+            deadends: HashTable::bounded(bytes),
+        }
+    }
+
+    pub fn stats(&self) -> Stats {
+        self.deadends.stats()
+    }
+
+    #[cfg(feature = "vct")]
+    pub fn deadends(&self) -> Vec<(u64, u8)> {
+        self.deadends.entries()
+    }
+
+    #[cfg(feature = "vct")]
+    pub fn load_deadends(&mut self, entries: &[(u64, u8)]) {
+        for &(key, limit) in entries {
+            self.deadends.insert(key, limit);
         }
     }
 
@@ -21,13 +44,14 @@ impl DFSSolver {
             return None;
         }
 
-        let hash = state.zobrist_hash();
-        if self.deadends.contains(&hash) {
+        // A deadend holds for smaller limits, so boards are keyed regardless of limits
+        let hash = state.game().board().zobrist_hash();
+        if matches!(self.deadends.get(hash), Some(limit) if limit >= state.limit) {
             return None;
         }
         let result = self.solve_move_pairs(state);
         if result.is_none() {
-            self.deadends.insert(hash);
+            self.deadends.insert(hash, state.limit);
         }
         result
     }
@@ -1,6 +1,8 @@
 use super::dfs::DFSSolver;
 use super::state::VCFState;
 use crate::mate::mate::*;
+#[cfg(feature = "vct")]
+use crate::mate::table::Stats;
 
 pub struct IDDFSSolver {
     solver: DFSSolver,
@@ -15,6 +17,29 @@ impl IDDFSSolver {
         }
     }
 
+    #[cfg(feature = "vct")]
+    pub fn init_bounded(limits: Vec<u8>, bytes: usize) -> Self {
+        Self {
+            solver: DFSSolver::init_bounded(bytes),
+            limits: limits,
+        }
+    }
+
+    #[cfg(feature = "vct")]
+    pub fn stats(&self) -> Stats {
+        self.solver.stats()
+    }
+
+    #[cfg(feature = "vct")]
+    pub fn deadends(&self) -> Vec<(u64, u8)> {
+        self.solver.deadends()
+    }
+
+    #[cfg(feature = "vct")]
+    pub fn load_deadends(&mut self, entries: &[(u64, u8)]) {
+        self.solver.load_deadends(entries)
+    }
+
     pub fn solve(&mut self, state: &mut VCFState) -> Option<Mate> {
         let max_limit = state.limit;
         for &limit in &self.limits {
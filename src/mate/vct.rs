@@ -8,8 +8,15 @@ mod solver;
 mod state;
 mod traverser;
 
+pub use proof::{Entry, Node};
+pub use solver::DFPNPVCTSolver;
 pub use solver::DFPNSVCTSolver;
 pub use solver::DFSVCTSolver;
+pub use solver::DFWPNSVCTSolver;
+pub use solver::LambdaVCTSolver;
 pub use solver::PNSVCTSolver;
+pub use solver::ParallelDFPNSVCTSolver;
+pub use solver::StepVCTSolver;
+pub use solver::TSSVCTSolver;
 pub use solver::VCTSolver;
 pub use state::VCTState;
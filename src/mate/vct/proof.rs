@@ -1,33 +1,182 @@
 use super::state::VCTState;
 use crate::board::Point;
-use crate::mate::state::State;
+use crate::mate::table::*;
 use std::collections::HashMap;
 use std::fmt;
+use std::sync::{Arc, RwLock};
 
 pub trait ProofTree {
     fn attacker_table(&mut self) -> &mut Table;
     fn defender_table(&mut self) -> &mut Table;
 }
 
+// Solved nodes shared among threads of a parallel search
+pub type SharedTable = Arc<RwLock<HashTable<Entry>>>;
+
 pub struct Table {
-    table: HashMap<u64, Node>,
+    table: HashTable<Entry>,
+    shared: Option<SharedTable>,
+    // Children of the nodes being traversed, only in a bounded table.
+    // Otherwise df-pn might evict a child by its sibling and search them in turn forever.
+    held: Option<Vec<HashMap<u64, Entry>>>,
 }
 
 impl Table {
     pub fn new() -> Self {
         Self {
-            table: HashMap::new(),
+            table: HashTable::new(),
+            shared: None,
+            held: None,
+        }
+    }
+
+    pub fn bounded(bytes: usize) -> Self {
+        Self {
+            table: HashTable::bounded(bytes),
+            shared: None,
+            held: Some(vec![]),
         }
     }
 
+    pub fn with_shared(shared: SharedTable) -> Self {
+        Self {
+            table: HashTable::new(),
+            shared: Some(shared),
+            held: None,
+        }
+    }
+
+    pub fn bounded_with_shared(bytes: usize, shared: SharedTable) -> Self {
+        Self {
+            table: HashTable::bounded(bytes),
+            shared: Some(shared),
+            held: Some(vec![]),
+        }
+    }
+
+    // A new entry replaces the old one of the same board even if it was searched with another limit.
+    // Otherwise df-pn would search the board again and again with the same threshold.
+    // Only a solved entry still holding for the limit is kept against an unsolved one.
     pub fn insert(&mut self, state: &VCTState, node: Node) {
-        let key = state.zobrist_hash();
-        self.table.insert(key, node.clone());
+        let key = state.key();
+        if !node.solved()
+            && self
+                .table
+                .get(key)
+                .and_then(|e| e.at(state.limit))
+                .map_or(false, |n| n.solved())
+        {
+            return;
+        }
+        let entry = Entry::new(node, state.limit);
+        self.table.insert(key, entry);
+        if let Some(held) = self.held.as_mut().and_then(|h| h.last_mut()) {
+            held.insert(key, entry);
+        }
+        if let Some(shared) = &self.shared {
+            if node.pn == 0 || node.dn == 0 {
+                shared.write().unwrap().insert(key, entry);
+            }
+        }
     }
 
     pub fn lookup_next(&self, state: &mut VCTState, next_move: Option<Point>) -> Option<Node> {
-        let key = state.next_zobrist_hash(next_move);
-        self.table.get(&key).map(|&c| c)
+        let (key, limit) = state.next_key(next_move);
+        let held = self
+            .held
+            .as_ref()
+            .and_then(|h| h.last()?.get(&key).copied());
+        let local = match held
+            .or_else(|| self.table.get(key))
+            .and_then(|e| e.at(limit))
+        {
+            Some(c) if c.pn == 0 || c.dn == 0 => return Some(c),
+            local => local,
+        };
+        // The shared table is skipped while another thread writes to it, instead of waiting
+        let shared = self
+            .shared
+            .as_ref()
+            .and_then(|s| s.try_read().ok()?.get(key).and_then(|e| e.at(limit)));
+        shared.or(local)
+    }
+
+    // Children inserted until leave are held apart from the bounded table
+    pub fn enter(&mut self) {
+        if let Some(held) = self.held.as_mut() {
+            held.push(HashMap::new());
+        }
+    }
+
+    pub fn leave(&mut self) {
+        if let Some(held) = self.held.as_mut() {
+            held.pop();
+        }
+    }
+
+    pub fn stats(&self) -> Stats {
+        self.table.stats()
+    }
+
+    // Unsolved nodes only guide the search from the root they were computed for
+    pub fn prune(&mut self) {
+        self.table.retain(|e| e.node.pn == 0 || e.node.dn == 0);
+    }
+
+    pub fn solved_entries(&self) -> Vec<(u64, Entry)> {
+        let mut result = self.table.entries();
+        result.retain(|(_, e)| e.node.pn == 0 || e.node.dn == 0);
+        result
+    }
+
+    pub fn load(&mut self, entries: &[(u64, Entry)]) {
+        for &(key, entry) in entries {
+            self.table.insert(key, entry);
+        }
+    }
+}
+
+// Node with the limit of the state it was searched with
+#[derive(Debug, Clone, PartialEq, Eq, Copy)]
+pub struct Entry {
+    pub node: Node,
+    pub limit: u8,
+}
+
+impl Entry {
+    pub fn new(node: Node, limit: u8) -> Self {
+        Self {
+            node: node,
+            limit: limit,
+        }
+    }
+
+    // A proof holds for larger limits and a disproof for smaller ones.
+    // The limit of the node, which is the one left at the end, is shifted by the difference.
+    pub fn at(&self, limit: u8) -> Option<Node> {
+        let node = self.node;
+        if node.pn == 0 && self.limit <= limit {
+            let shifted = node.limit.saturating_add(limit - self.limit);
+            Some(Node::new(node.pn, node.dn, shifted))
+        } else if node.dn == 0 && self.limit >= limit {
+            let shifted = node.limit.saturating_sub(self.limit - limit);
+            Some(Node::new(node.pn, node.dn, shifted))
+        } else if self.limit == limit {
+            Some(node)
+        } else {
+            None
+        }
+    }
+}
+
+impl Priority for Entry {
+    fn priority(&self) -> u64 {
+        self.node.priority()
+    }
+
+    // Proofs are needed to resolve the mate
+    fn pinned(&self) -> bool {
+        self.node.pn == 0
     }
 }
 
@@ -73,6 +222,10 @@ impl Node {
         self.pn == 0
     }
 
+    pub fn solved(&self) -> bool {
+        self.pn == 0 || self.dn == 0
+    }
+
     pub fn min_pn_sum_dn(&self, another: Self) -> Self {
         Self::new(
             self.pn.min(another.pn),
@@ -88,6 +241,31 @@ impl Node {
             self.limit.min(another.limit),
         )
     }
+
+    pub fn min_pn_max_dn(&self, another: Self) -> Self {
+        Self::new(
+            self.pn.min(another.pn),
+            self.dn.max(another.dn),
+            self.limit.min(another.limit),
+        )
+    }
+
+    pub fn max_pn_min_dn(&self, another: Self) -> Self {
+        Self::new(
+            self.pn.max(another.pn),
+            self.dn.min(another.dn),
+            self.limit.min(another.limit),
+        )
+    }
+}
+
+impl Priority for Node {
+    // Solved nodes first, then nodes with more remaining limit, then nodes with more effort
+    fn priority(&self) -> u64 {
+        let solved = (self.pn == 0 || self.dn == 0) as u64;
+        let effort = self.pn.saturating_add(self.dn).min(u32::MAX - 1) as u64;
+        solved << 40 | (self.limit as u64) << 32 | effort
+    }
 }
 
 impl fmt::Display for Node {
@@ -105,3 +283,36 @@ impl fmt::Display for Node {
         write!(f, "(pn: {}, dn: {})", pn, dn)
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::*;
+
+    #[test]
+    fn test_insert() -> Result<(), String> {
+        let board = Board::from_str_with("H8/I9", Notation::Alphanumeric)?;
+        let mut state = VCTState::init(&board, Player::Black, 2);
+        let key = state.key();
+
+        // A proof at a smaller limit still holds
+        let mut table = Table::new();
+        table.insert(&state, Node::zero_pn(1));
+        state.limit = 3;
+        table.insert(&state, Node::unit_pn(1, 3));
+        assert_eq!(table.table.get(key), Some(Entry::new(Node::zero_pn(1), 2)));
+
+        // A disproof at a smaller limit does not
+        let mut table = Table::new();
+        state.limit = 2;
+        table.insert(&state, Node::zero_dn(0));
+        state.limit = 3;
+        table.insert(&state, Node::unit_pn(1, 3));
+        assert_eq!(
+            table.table.get(key),
+            Some(Entry::new(Node::unit_pn(1, 3), 3))
+        );
+
+        Ok(())
+    }
+}
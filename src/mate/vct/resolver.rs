@@ -1,11 +1,13 @@
-use super::helper::VCFHelper;
+use super::searcher::Searcher;
 use super::state::VCTState;
+use crate::board::Point;
 use crate::mate::game::*;
 use crate::mate::mate::Mate;
 use crate::mate::state::State;
 use crate::mate::vct::proof::*;
 
-pub trait Resolver: ProofTree + VCFHelper {
+// Bounded tables may evict solved children, which are searched again here
+pub trait Resolver: Searcher {
     fn resolve(&mut self, state: &mut VCTState) -> Option<Mate> {
         self.resolve_attacks(state)
     }
@@ -20,17 +22,30 @@ pub trait Resolver: ProofTree + VCFHelper {
             };
         }
 
-        for attack in state.empties() {
-            let maybe_node = self.attacker_table().lookup_next(state, Some(attack));
-            let node = maybe_node.unwrap_or(Node::inf());
-            if node.proven() {
-                return state.into_play(Some(attack), |s| {
-                    self.resolve_defences(s).map(|m| m.unshift(attack))
-                });
-            }
+        if let Some(attack) = self.proven_attack(state) {
+            return state.into_play(Some(attack), |s| {
+                self.resolve_defences(s).map(|m| m.unshift(attack))
+            });
         }
 
-        self.solve_attacker_vcf(state)
+        if let Some(mate) = self.solve_attacker_vcf(state) {
+            return Some(mate);
+        }
+
+        if !self.search_attacks(state, Node::inf()).proven() {
+            return None;
+        }
+        let attack = self.proven_attack(state)?;
+        state.into_play(Some(attack), |s| {
+            self.resolve_defences(s).map(|m| m.unshift(attack))
+        })
+    }
+
+    fn proven_attack(&mut self, state: &mut VCTState) -> Option<Point> {
+        state.empties().into_iter().find(|&attack| {
+            let maybe_node = self.attacker_table().lookup_next(state, Some(attack));
+            maybe_node.map_or(false, |n| n.proven())
+        })
     }
 
     fn resolve_defences(&mut self, state: &mut VCTState) -> Option<Mate> {
@@ -49,7 +64,14 @@ pub trait Resolver: ProofTree + VCFHelper {
         let mut best = None;
         for (defence, _) in defences {
             let maybe_node = self.defender_table().lookup_next(state, Some(defence));
-            let node = maybe_node.unwrap_or(Node::inf());
+            let node = match maybe_node {
+                Some(node) => node,
+                None => state.into_play(Some(defence), |s| {
+                    let result = self.search_attacks(s, Node::inf());
+                    self.defender_table().insert(s, result);
+                    result
+                }),
+            };
             if node.proven() && node.limit < min_limit {
                 min_limit = node.limit;
                 best.replace(defence);
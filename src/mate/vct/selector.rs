@@ -1,12 +1,19 @@
 use crate::board::Point;
+use crate::mate::state::State;
+use crate::mate::vct::generator::Generator;
 use crate::mate::vct::proof::*;
 use crate::mate::vct::state::VCTState;
+use lru::LruCache;
 
 pub struct Selection {
     pub best: Option<Point>,
     pub current: Node,
     pub next1: Node,
     pub next2: Node,
+    // Unsolved children other than the one of the max number, which weak proof numbers add
+    pub n_others: u32,
+    // Cost of the edge to the best child, which df-pn+ adds to unsolved children
+    pub cost: u32,
 }
 
 pub trait Selector: ProofTree {
@@ -38,6 +45,8 @@ pub trait Selector: ProofTree {
             current: current,
             next1: next1,
             next2: next2,
+            n_others: 0,
+            cost: 0,
         }
     }
 
@@ -69,6 +78,256 @@ pub trait Selector: ProofTree {
             current: current,
             next1: next1,
             next2: next2,
+            n_others: 0,
+            cost: 0,
         }
     }
 }
+
+/*
+Weak proof number search is proposed in the following paper:
+
+Ueda, Toru, et al.
+"Weak proof-number search."
+International Conference on Computers and Games. Springer, 2008.
+
+Sums over children count a shared grandchild twice when children transpose into each other,
+which is very common in threat sequences. Weak proof numbers take the maximum plus the number of the other children instead.
+*/
+
+pub trait WPNSelector: ProofTree {
+    fn select_attack(&mut self, state: &mut VCTState, attacks: &[Point]) -> Selection {
+        let limit = state.limit;
+        let mut best: Option<Point> = Some(attacks[0]);
+        let mut current = Node::zero_dn(limit);
+        let mut next1 = Node::zero_dn(limit);
+        let mut next2 = Node::zero_dn(limit);
+        let mut n_alive = 0;
+        let init = Node::unit_dn(attacks.len() as u32, limit); // trick
+        for &attack in attacks {
+            let maybe_child = self.attacker_table().lookup_next(state, Some(attack));
+            let child = maybe_child.unwrap_or(init);
+            current = current.min_pn_max_dn(child);
+            n_alive += (child.dn != 0) as u32;
+            if child.pn < next1.pn {
+                best.replace(attack);
+                next2 = next1;
+                next1 = child;
+            } else if child.pn < next2.pn {
+                next2 = child;
+            }
+            if current.pn == 0 {
+                current.dn = INF;
+                break;
+            }
+        }
+        let n_others = n_alive.saturating_sub(1);
+        if current.pn != 0 {
+            current.dn = current.dn.saturating_add(n_others);
+        }
+        Selection {
+            best: best,
+            current: current,
+            next1: next1,
+            next2: next2,
+            n_others: n_others,
+            cost: 0,
+        }
+    }
+
+    fn select_defence(&mut self, state: &mut VCTState, defences: &[Point]) -> Selection {
+        let limit = state.limit;
+        let mut best: Option<Point> = Some(defences[0]);
+        let mut current = Node::zero_pn(limit - 1);
+        let mut next1 = Node::zero_pn(limit - 1);
+        let mut next2 = Node::zero_pn(limit - 1);
+        let mut n_alive = 0;
+        let init = Node::unit_pn(defences.len() as u32, limit - 1); // trick
+        for &defence in defences {
+            let maybe_child = self.defender_table().lookup_next(state, Some(defence));
+            let child = maybe_child.unwrap_or(init);
+            current = current.max_pn_min_dn(child);
+            n_alive += (child.pn != 0) as u32;
+            if child.dn < next1.dn {
+                best.replace(defence);
+                next2 = next1;
+                next1 = child;
+            } else if child.dn < next2.dn {
+                next2 = child;
+            }
+            if current.dn == 0 {
+                current.pn = INF;
+                break;
+            }
+        }
+        let n_others = n_alive.saturating_sub(1);
+        if current.dn != 0 {
+            current.pn = current.pn.saturating_add(n_others);
+        }
+        Selection {
+            best: best,
+            current: current,
+            next1: next1,
+            next2: next2,
+            n_others: n_others,
+            cost: 0,
+        }
+    }
+}
+
+/*
+Df-pn+ is proposed in the following thesis:
+
+Nagai, Ayumu.
+"Df-pn algorithm for searching AND/OR trees and its applications."
+PhD thesis, Department of Information Science, University of Tokyo (2002).
+
+A three starts from the number of defences against its threat and a four from the unit,
+while another attack starts from the trick and costs more for less potential than the best one.
+A counter four starts from the unit and another defence costs more for less potential likewise.
+Estimates are computed once for each list of moves.
+*/
+
+const POTENTIAL_PER_COST: u32 = 4;
+
+pub trait HeuristicSelector: ProofTree + Generator {
+    fn attack_estimates_cache(&mut self) -> &mut LruCache<u64, Vec<(Node, u32)>>;
+    fn defence_estimates_cache(&mut self) -> &mut LruCache<u64, Vec<(Node, u32)>>;
+
+    fn select_attack(&mut self, state: &mut VCTState, attacks: &[Point]) -> Selection {
+        let limit = state.limit;
+        let estimates = self.estimate_attacks(state, attacks);
+        let mut best: Option<Point> = Some(attacks[0]);
+        let mut best_cost = 0;
+        let mut current = Node::zero_dn(limit);
+        let mut next1 = Node::zero_dn(limit);
+        let mut next2 = Node::zero_dn(limit);
+        for (&attack, &(init, cost)) in attacks.iter().zip(estimates.iter()) {
+            let maybe_child = self.attacker_table().lookup_next(state, Some(attack));
+            let mut child = maybe_child.unwrap_or(init);
+            if child.pn != 0 && child.dn != 0 {
+                child.pn = child.pn.saturating_add(cost);
+            }
+            current = current.min_pn_sum_dn(child);
+            if child.pn < next1.pn {
+                best.replace(attack);
+                best_cost = cost;
+                next2 = next1;
+                next1 = child;
+            } else if child.pn < next2.pn {
+                next2 = child;
+            }
+            if current.pn == 0 {
+                current.dn = INF;
+                break;
+            }
+        }
+        Selection {
+            best: best,
+            current: current,
+            next1: next1,
+            next2: next2,
+            n_others: 0,
+            cost: best_cost,
+        }
+    }
+
+    fn select_defence(&mut self, state: &mut VCTState, defences: &[Point]) -> Selection {
+        let limit = state.limit;
+        let estimates = self.estimate_defences(state, defences);
+        let mut best: Option<Point> = Some(defences[0]);
+        let mut best_cost = 0;
+        let mut current = Node::zero_pn(limit - 1);
+        let mut next1 = Node::zero_pn(limit - 1);
+        let mut next2 = Node::zero_pn(limit - 1);
+        for (&defence, &(init, cost)) in defences.iter().zip(estimates.iter()) {
+            let maybe_child = self.defender_table().lookup_next(state, Some(defence));
+            let mut child = maybe_child.unwrap_or(init);
+            if child.pn != 0 && child.dn != 0 {
+                child.dn = child.dn.saturating_add(cost);
+            }
+            current = current.min_dn_sum_pn(child);
+            if child.dn < next1.dn {
+                best.replace(defence);
+                best_cost = cost;
+                next2 = next1;
+                next1 = child;
+            } else if child.dn < next2.dn {
+                next2 = child;
+            }
+            if current.dn == 0 {
+                current.pn = INF;
+                break;
+            }
+        }
+        Selection {
+            best: best,
+            current: current,
+            next1: next1,
+            next2: next2,
+            n_others: 0,
+            cost: best_cost,
+        }
+    }
+
+    fn estimate_attacks(&mut self, state: &mut VCTState, attacks: &[Point]) -> Vec<(Node, u32)> {
+        let key = state.zobrist_hash();
+        if let Some(hit) = self.attack_estimates_cache().get(&key) {
+            return hit.clone();
+        }
+        let limit = state.limit;
+        let top = state.potential(attacks[0]);
+        let trick = Node::unit_dn(attacks.len() as u32, limit);
+        let mut result = Vec::with_capacity(attacks.len());
+        for &attack in attacks {
+            if state.is_four_move(attack) {
+                result.push((Node::unit_dn(1, limit), 0));
+                continue;
+            }
+            if !state.is_three_move(attack) {
+                let cost = (top - state.potential(attack)) as u32 / POTENTIAL_PER_COST;
+                result.push((trick, cost));
+                continue;
+            }
+            // Defences are generated only after threes since threat searches cost high
+            let init = state.into_play(Some(attack), |s| {
+                if limit <= 1 || s.check_event().is_some() {
+                    return trick;
+                }
+                match self.generate_defences(s) {
+                    Ok(defences) => Node::unit_dn(defences.len() as u32, limit),
+                    Err(node) => {
+                        // Resolvers look up solved children in the table
+                        self.attacker_table().insert(s, node);
+                        node
+                    }
+                }
+            });
+            result.push((init, 0));
+        }
+        self.attack_estimates_cache().put(key, result.clone());
+        result
+    }
+
+    fn estimate_defences(&mut self, state: &mut VCTState, defences: &[Point]) -> Vec<(Node, u32)> {
+        let key = state.zobrist_hash();
+        if let Some(hit) = self.defence_estimates_cache().get(&key) {
+            return hit.clone();
+        }
+        let limit = state.limit - 1;
+        let top = state.potential(defences[0]);
+        let mut result = Vec::with_capacity(defences.len());
+        for &defence in defences {
+            // A counter four leaves the attacker just one move
+            if state.is_four_move(defence) {
+                result.push((Node::new(1, 1, limit), 0));
+                continue;
+            }
+            let init = Node::unit_pn(defences.len() as u32, limit);
+            let cost = top.saturating_sub(state.potential(defence)) as u32 / POTENTIAL_PER_COST;
+            result.push((init, cost));
+        }
+        self.defence_estimates_cache().put(key, result.clone());
+        result
+    }
+}
@@ -1,15 +1,30 @@
+mod dfpnp;
 mod dfpns;
 mod dfs;
+mod dfwpns;
+mod lambda;
+mod parallel;
 mod pns;
+mod step;
+mod tss;
 
+pub use dfpnp::DFPNPVCTSolver;
 pub use dfpns::DFPNSVCTSolver;
 pub use dfs::DFSVCTSolver;
+pub use dfwpns::DFWPNSVCTSolver;
+pub use lambda::LambdaVCTSolver;
+pub use parallel::ParallelDFPNSVCTSolver;
 pub use pns::PNSVCTSolver;
+pub use step::StepVCTSolver;
+pub use tss::TSSVCTSolver;
 
+use super::proof::{Node, Table};
 use super::resolver::Resolver;
 use super::searcher::Searcher;
 use super::state::VCTState;
 use crate::mate::mate::Mate;
+use crate::mate::table::Stats;
+use crate::mate::vcf;
 
 pub trait VCTSolver: Searcher + Resolver {
     fn solve(&mut self, state: &mut VCTState) -> Option<Mate> {
@@ -19,4 +34,20 @@ pub trait VCTSolver: Searcher + Resolver {
             None
         }
     }
+
+    // Mate of the attacker against every move of the defender to move
+    fn solve_defended(&mut self, state: &mut VCTState) -> Option<Mate> {
+        if self.search_defences(state, Node::inf()).proven() {
+            self.resolve_defences(state)
+        } else {
+            None
+        }
+    }
+}
+
+// Stats of the proof tables and the VCF deadends of a df-pn solver
+fn solver_stats(tables: [&Table; 2], vcf_solvers: [&vcf::IDDFSSolver; 2]) -> Stats {
+    let tables = tables[0].stats().merge(tables[1].stats());
+    let deadends = vcf_solvers[0].stats().merge(vcf_solvers[1].stats());
+    tables.merge(deadends)
 }
@@ -1,4 +1,6 @@
 use crate::board::Point;
+use crate::mate::snapshot::Snapshot;
+use crate::mate::table::Stats;
 use crate::mate::vcf;
 use crate::mate::vct::generator::Generator;
 use crate::mate::vct::helper::VCFHelper;
@@ -6,7 +8,7 @@ use crate::mate::vct::proof::*;
 use crate::mate::vct::resolver::Resolver;
 use crate::mate::vct::searcher::Searcher;
 use crate::mate::vct::selector::*;
-use crate::mate::vct::solver::VCTSolver;
+use crate::mate::vct::solver::{solver_stats, VCTSolver};
 use crate::mate::vct::traverser::*;
 use lru::LruCache;
 
@@ -34,6 +36,50 @@ impl DFPNSVCTSolver {
             defences_cache: LruCache::new(1000),
         }
     }
+
+    // Tables take 3/8 of the bytes each, and VCF deadends take 1/8 each
+    pub fn init_bounded(attacker_vcf_depth: u8, defender_vcf_depth: u8, bytes: usize) -> Self {
+        Self {
+            attacker_table: Table::bounded(bytes / 8 * 3),
+            defender_table: Table::bounded(bytes / 8 * 3),
+            attacker_vcf_depth: attacker_vcf_depth,
+            defender_vcf_depth: defender_vcf_depth,
+            attacker_vcf_solver: vcf::IDDFSSolver::init_bounded([1].to_vec(), bytes / 8),
+            defender_vcf_solver: vcf::IDDFSSolver::init_bounded([1].to_vec(), bytes / 8),
+            attacks_cache: LruCache::new(1000),
+            defences_cache: LruCache::new(1000),
+        }
+    }
+
+    pub fn prune(&mut self) {
+        self.attacker_table.prune();
+        self.defender_table.prune();
+    }
+
+    pub fn stats(&self) -> Stats {
+        solver_stats(
+            [&self.attacker_table, &self.defender_table],
+            [&self.attacker_vcf_solver, &self.defender_vcf_solver],
+        )
+    }
+
+    pub fn load(&mut self, snapshot: &Snapshot) {
+        self.attacker_table.load(&snapshot.attacker_table());
+        self.defender_table.load(&snapshot.defender_table());
+        self.attacker_vcf_solver
+            .load_deadends(&snapshot.attacker_deadends());
+        self.defender_vcf_solver
+            .load_deadends(&snapshot.defender_deadends());
+    }
+
+    pub fn save(&self, snapshot: &mut Snapshot) {
+        snapshot.store(
+            self.attacker_table.solved_entries(),
+            self.defender_table.solved_entries(),
+            self.attacker_vcf_solver.deadends(),
+            self.defender_vcf_solver.deadends(),
+        );
+    }
 }
 
 impl VCTSolver for DFPNSVCTSolver {}
@@ -0,0 +1,121 @@
+use crate::board::Point;
+use crate::mate::table::Stats;
+use crate::mate::vcf;
+use crate::mate::vct::generator::Generator;
+use crate::mate::vct::helper::VCFHelper;
+use crate::mate::vct::proof::*;
+use crate::mate::vct::resolver::Resolver;
+use crate::mate::vct::searcher::Searcher;
+use crate::mate::vct::selector::*;
+use crate::mate::vct::solver::solver_stats;
+use crate::mate::vct::solver::VCTSolver;
+use crate::mate::vct::state::VCTState;
+use crate::mate::vct::traverser::*;
+use lru::LruCache;
+
+/*
+Df-pn with weak proof numbers, which does not over-count transpositions.
+Boards only gain stones and a board is always reached with the same limit in a search,
+so the search space is a DAG and the value of a node does not depend on the path to it (no GHI).
+*/
+
+pub struct DFWPNSVCTSolver {
+    attacker_table: Table,
+    defender_table: Table,
+    attacker_vcf_depth: u8,
+    defender_vcf_depth: u8,
+    attacker_vcf_solver: vcf::IDDFSSolver,
+    defender_vcf_solver: vcf::IDDFSSolver,
+    attacks_cache: LruCache<u64, Result<Vec<Point>, Node>>,
+    defences_cache: LruCache<u64, Result<Vec<Point>, Node>>,
+}
+
+impl DFWPNSVCTSolver {
+    pub fn init(attacker_vcf_depth: u8, defender_vcf_depth: u8) -> Self {
+        Self {
+            attacker_table: Table::new(),
+            defender_table: Table::new(),
+            attacker_vcf_depth: attacker_vcf_depth,
+            defender_vcf_depth: defender_vcf_depth,
+            attacker_vcf_solver: vcf::IDDFSSolver::init([1].to_vec()),
+            defender_vcf_solver: vcf::IDDFSSolver::init([1].to_vec()),
+            attacks_cache: LruCache::new(1000),
+            defences_cache: LruCache::new(1000),
+        }
+    }
+
+    pub fn stats(&self) -> Stats {
+        solver_stats(
+            [&self.attacker_table, &self.defender_table],
+            [&self.attacker_vcf_solver, &self.defender_vcf_solver],
+        )
+    }
+}
+
+impl VCTSolver for DFWPNSVCTSolver {}
+
+impl Searcher for DFWPNSVCTSolver {}
+
+impl Generator for DFWPNSVCTSolver {
+    fn attacks_cache(&mut self) -> &mut LruCache<u64, Result<Vec<Point>, Node>> {
+        &mut self.attacks_cache
+    }
+
+    fn defences_cache(&mut self) -> &mut LruCache<u64, Result<Vec<Point>, Node>> {
+        &mut self.defences_cache
+    }
+}
+
+impl VCFHelper for DFWPNSVCTSolver {
+    fn attacker_vcf_depth(&self) -> u8 {
+        self.attacker_vcf_depth
+    }
+
+    fn defender_vcf_depth(&self) -> u8 {
+        self.defender_vcf_depth
+    }
+
+    fn attacker_vcf_solver(&mut self) -> &mut vcf::IDDFSSolver {
+        &mut self.attacker_vcf_solver
+    }
+
+    fn defender_vcf_solver(&mut self) -> &mut vcf::IDDFSSolver {
+        &mut self.defender_vcf_solver
+    }
+}
+
+impl Traverser for DFWPNSVCTSolver {
+    fn next_threshold_attack(&self, selection: &Selection, threshold: Node) -> Node {
+        DFWPNSTraverser::next_threshold_attack(self, selection, threshold)
+    }
+
+    fn next_threshold_defence(&self, selection: &Selection, threshold: Node) -> Node {
+        DFWPNSTraverser::next_threshold_defence(self, selection, threshold)
+    }
+}
+
+impl DFWPNSTraverser for DFWPNSVCTSolver {}
+
+impl Selector for DFWPNSVCTSolver {
+    fn select_attack(&mut self, state: &mut VCTState, attacks: &[Point]) -> Selection {
+        WPNSelector::select_attack(self, state, attacks)
+    }
+
+    fn select_defence(&mut self, state: &mut VCTState, defences: &[Point]) -> Selection {
+        WPNSelector::select_defence(self, state, defences)
+    }
+}
+
+impl WPNSelector for DFWPNSVCTSolver {}
+
+impl ProofTree for DFWPNSVCTSolver {
+    fn attacker_table(&mut self) -> &mut Table {
+        &mut self.attacker_table
+    }
+
+    fn defender_table(&mut self) -> &mut Table {
+        &mut self.defender_table
+    }
+}
+
+impl Resolver for DFWPNSVCTSolver {}
@@ -0,0 +1,213 @@
+use crate::board::Point;
+use crate::mate::game::*;
+use crate::mate::mate::Mate;
+use crate::mate::state::State;
+use crate::mate::table::HashTable;
+use crate::mate::vcf;
+use crate::mate::vct::helper::VCFHelper;
+use crate::mate::vct::state::VCTState;
+use lru::LruCache;
+
+/*
+Lambda search is proposed in the following paper:
+
+Thomsen, Thomas.
+"Lambda-search in game trees—with application to Go."
+International Conference on Computers and Games. Springer, Berlin, Heidelberg, 2000.
+
+A λ^n attack is a move after which the attacker wins by λ^(n-1) if the defender passes,
+and λ^1 is VCF. Lower orders are tried first at every attacker node.
+Attacks of the third order and higher are the moves whose threats are found by the search after a pass,
+and defences against them are the moves after which the attacker does not win by λ^(n-1) any more.
+Threats of every order are searched within attacker_vcf_depth.
+*/
+
+pub struct LambdaVCTSolver {
+    order: u8,
+    attacker_vcf_depth: u8,
+    defender_vcf_depth: u8,
+    attacker_vcf_solver: vcf::IDDFSSolver,
+    defender_vcf_solver: vcf::IDDFSSolver,
+    // Limits for each order, since a deadend holds for smaller limits
+    deadends: Vec<HashTable<u8>>,
+    attacks_cache: LruCache<(u64, u8), Vec<Point>>,
+    threats_cache: LruCache<(u64, u8), Option<Mate>>,
+}
+
+impl LambdaVCTSolver {
+    pub fn init(order: u8, attacker_vcf_depth: u8, defender_vcf_depth: u8) -> Self {
+        Self {
+            order: order,
+            attacker_vcf_depth: attacker_vcf_depth,
+            defender_vcf_depth: defender_vcf_depth,
+            attacker_vcf_solver: vcf::IDDFSSolver::init([1].to_vec()),
+            defender_vcf_solver: vcf::IDDFSSolver::init([1].to_vec()),
+            deadends: (0..=order).map(|_| HashTable::new()).collect(),
+            attacks_cache: LruCache::new(1000),
+            threats_cache: LruCache::new(1000),
+        }
+    }
+
+    pub fn solve(&mut self, state: &mut VCTState) -> Option<Mate> {
+        self.solve_attacks(state, self.order)
+    }
+
+    fn solve_attacks(&mut self, state: &mut VCTState, order: u8) -> Option<Mate> {
+        if state.limit == 0 {
+            return None;
+        }
+
+        if let Some(event) = state.check_event() {
+            return match event {
+                Defeated(_) => None,
+                Forced(attack) => state.into_play(Some(attack), |s| {
+                    self.solve_defences(s, order).map(|m| m.unshift(attack))
+                }),
+            };
+        }
+
+        if let Some(mate) = self.solve_attacker_vcf(state) {
+            return Some(mate);
+        }
+
+        // A higher order is tried only if the limit leaves room for its threat
+        for n in 2..=order.min(state.limit - 1).max(2) {
+            let result = self.solve_order(state, n);
+            if result.is_some() {
+                return result;
+            }
+        }
+        None
+    }
+
+    fn solve_order(&mut self, state: &mut VCTState, n: u8) -> Option<Mate> {
+        let key = state.key();
+        if matches!(self.deadends[n as usize].get(key), Some(limit) if limit >= state.limit) {
+            return None;
+        }
+        for attack in self.generate_attacks(state, n) {
+            let result = state.into_play(Some(attack), |s| self.solve_defences(s, n));
+            if let Some(mate) = result {
+                return Some(mate.unshift(attack));
+            }
+        }
+        self.deadends[n as usize].insert(key, state.limit);
+        None
+    }
+
+    fn solve_defences(&mut self, state: &mut VCTState, n: u8) -> Option<Mate> {
+        if let Some(event) = state.check_event() {
+            return match event {
+                Defeated(end) => Some(Mate::new(end, vec![])),
+                Forced(defence) => {
+                    if state.limit <= 1 {
+                        None
+                    } else {
+                        state.into_play(Some(defence), |s| {
+                            self.solve_attacks(s, n).map(|m| m.unshift(defence))
+                        })
+                    }
+                }
+            };
+        }
+
+        if state.limit <= 1 {
+            return None;
+        }
+
+        let threat = self.solve_threat(state, n)?;
+
+        if self.solve_defender_vcf(state).is_some() {
+            return None;
+        }
+
+        let mut result = Mate::new(Unknown, vec![]);
+        for defence in self.generate_defences(state, &threat, n) {
+            let mate = state.into_play(Some(defence), |s| {
+                // Defences after which the threat still holds lose to it
+                let lower = if n <= 2 { None } else { self.solve_lower(s, n) };
+                lower.or_else(|| self.solve_attacks(s, n))
+            })?;
+            result = Mate::preferred(result, mate.unshift(defence));
+        }
+        Some(result)
+    }
+
+    // The attacker wins by λ^(n-1) if the defender passes
+    fn solve_threat(&mut self, state: &mut VCTState, n: u8) -> Option<Mate> {
+        if n <= 2 {
+            return self.solve_attacker_threat(state);
+        }
+        state.into_play(None, |s| self.solve_lower(s, n))
+    }
+
+    // The attacker wins by λ^(n-1) within attacker_vcf_depth as VCF threats do
+    fn solve_lower(&mut self, state: &mut VCTState, n: u8) -> Option<Mate> {
+        let limit = state.limit;
+        state.set_limit(limit.min(self.attacker_vcf_depth));
+        let key = (state.zobrist_hash(), n);
+        let result = match self.threats_cache.get(&key) {
+            Some(hit) => hit.clone(),
+            None => {
+                let result = self.solve_attacks(state, n - 1);
+                self.threats_cache.put(key, result.clone());
+                result
+            }
+        };
+        state.set_limit(limit);
+        result
+    }
+
+    fn generate_attacks(&mut self, state: &mut VCTState, n: u8) -> Vec<Point> {
+        let key = (state.zobrist_hash(), n);
+        if let Some(hit) = self.attacks_cache.get(&key) {
+            return hit.clone();
+        }
+        let maybe_threat = self.solve_defender_threat(state);
+        let maybe_threat_defences = maybe_threat.map(|t| state.threat_defences(&t));
+        // Higher orders also take moves which only prepare threats
+        let min = if n <= 2 { 3 } else { 2 };
+        let mut result = state.sorted_potentials(min, maybe_threat_defences);
+        result.retain(|&x| !state.is_forbidden_move(x.0));
+        let mut result: Vec<_> = result.into_iter().map(|x| x.0).collect();
+        if n > 2 {
+            // The search after a pass decides which of them are attacks
+            result.retain(|&attack| {
+                state.into_play(Some(attack), |s| self.solve_threat(s, n).is_some())
+            });
+        }
+        self.attacks_cache.put(key, result.clone());
+        result
+    }
+
+    // Defences against a VCF are found along its path, but a higher order threat
+    // may be refuted anywhere in its tree, so all moves are tried for it.
+    fn generate_defences(&mut self, state: &mut VCTState, threat: &Mate, n: u8) -> Vec<Point> {
+        let defences = if n <= 2 {
+            state.threat_defences(threat)
+        } else {
+            state.empties()
+        };
+        let mut result = state.sort_by_potential(defences);
+        result.retain(|&x| !state.is_forbidden_move(x.0));
+        result.into_iter().map(|x| x.0).collect()
+    }
+}
+
+impl VCFHelper for LambdaVCTSolver {
+    fn attacker_vcf_depth(&self) -> u8 {
+        self.attacker_vcf_depth
+    }
+
+    fn defender_vcf_depth(&self) -> u8 {
+        self.defender_vcf_depth
+    }
+
+    fn attacker_vcf_solver(&mut self) -> &mut vcf::IDDFSSolver {
+        &mut self.attacker_vcf_solver
+    }
+
+    fn defender_vcf_solver(&mut self) -> &mut vcf::IDDFSSolver {
+        &mut self.defender_vcf_solver
+    }
+}
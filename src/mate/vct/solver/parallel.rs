@@ -0,0 +1,361 @@
+use crate::board::Point;
+use crate::mate::mate::Mate;
+use crate::mate::state::State;
+use crate::mate::table::{HashTable, Stats};
+use crate::mate::vcf;
+use crate::mate::vct::generator::Generator;
+use crate::mate::vct::helper::VCFHelper;
+use crate::mate::vct::proof::*;
+use crate::mate::vct::resolver::Resolver;
+use crate::mate::vct::searcher::Searcher;
+use crate::mate::vct::selector::*;
+use crate::mate::vct::solver::{solver_stats, VCTSolver};
+use crate::mate::vct::state::VCTState;
+use crate::mate::vct::traverser::*;
+use lru::LruCache;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex, RwLock};
+use std::thread;
+
+/*
+Parallel df-pn with virtual loss at the root:
+Every worker runs df-pn from the root over the root attacks, whose nodes are shared.
+An attack under search by other workers counts their virtual losses in its proof number,
+so a worker takes another one, and goes back to the root as soon as its attack exceeds the second best as in df-pn.
+Workers keep their own tables below the root and share solved nodes, so a subtree proven by one worker is not searched again by others.
+The first proof stops all workers, and the proof tree is resolved through the tables of the worker which found it,
+since only that worker stored the proof of the root attack.
+*/
+
+// Proof number added to a root attack for each worker searching it
+const VIRTUAL_LOSS: u32 = 4;
+
+pub struct ParallelDFPNSVCTSolver {
+    n_threads: usize,
+    attacker_vcf_depth: u8,
+    defender_vcf_depth: u8,
+    table_bytes: Option<usize>,
+    stats: Stats,
+}
+
+impl ParallelDFPNSVCTSolver {
+    pub fn init(n_threads: usize, attacker_vcf_depth: u8, defender_vcf_depth: u8) -> Self {
+        Self {
+            n_threads: n_threads.max(1),
+            attacker_vcf_depth: attacker_vcf_depth,
+            defender_vcf_depth: defender_vcf_depth,
+            table_bytes: None,
+            stats: Stats::default(),
+        }
+    }
+
+    // Shared tables take 1/4 of the bytes each, and workers share the rest equally
+    pub fn init_bounded(
+        n_threads: usize,
+        attacker_vcf_depth: u8,
+        defender_vcf_depth: u8,
+        bytes: usize,
+    ) -> Self {
+        Self {
+            table_bytes: Some(bytes),
+            ..Self::init(n_threads, attacker_vcf_depth, defender_vcf_depth)
+        }
+    }
+
+    // Stats of the tables in the last solve
+    pub fn stats(&self) -> Stats {
+        self.stats
+    }
+
+    pub fn solve(&mut self, state: &mut VCTState) -> Option<Mate> {
+        let shared_table = || match self.table_bytes {
+            Some(bytes) => HashTable::bounded(bytes / 4),
+            None => HashTable::new(),
+        };
+        let attacker_shared: SharedTable = Arc::new(RwLock::new(shared_table()));
+        let defender_shared: SharedTable = Arc::new(RwLock::new(shared_table()));
+        let worker_bytes = self.table_bytes.map(|bytes| bytes / 2 / self.n_threads);
+        let stop = AtomicBool::new(false);
+        let mut workers: Vec<_> = (0..self.n_threads)
+            .map(|_| {
+                Worker::init(
+                    self.attacker_vcf_depth,
+                    self.defender_vcf_depth,
+                    attacker_shared.clone(),
+                    defender_shared.clone(),
+                    worker_bytes,
+                    &stop,
+                )
+            })
+            .collect();
+
+        let result = self.solve_with(state, &mut workers);
+        let shared_stats = attacker_shared.read().unwrap().stats();
+        let shared_stats = shared_stats.merge(defender_shared.read().unwrap().stats());
+        self.stats = workers
+            .iter()
+            .fold(shared_stats, |stats, worker| stats.merge(worker.stats()));
+        result
+    }
+
+    fn solve_with(&self, state: &mut VCTState, workers: &mut [Worker]) -> Option<Mate> {
+        if state.limit == 0 {
+            return None;
+        }
+        let root_attacks = match state.check_event() {
+            Some(_) => None,
+            None => workers[0].generate_attacks(state).ok(),
+        };
+        let attacks = match root_attacks {
+            Some(attacks) if self.n_threads > 1 => attacks,
+            // Nothing to split
+            _ => return workers[0].solve(state),
+        };
+
+        let root = Root::init(&attacks, state.limit);
+        let winner = Mutex::new(None);
+        thread::scope(|s| {
+            for (i, worker) in workers.iter_mut().enumerate() {
+                let mut state = state.clone();
+                let (root, winner) = (&root, &winner);
+                s.spawn(move || {
+                    if worker.search_root(&mut state, root) {
+                        winner.lock().unwrap().get_or_insert(i);
+                    }
+                });
+            }
+        });
+
+        let winner = winner.into_inner().unwrap()?;
+        workers[winner].resolve(state)
+    }
+}
+
+// Nodes of the root attacks with the number of workers searching each
+struct Root<'a> {
+    attacks: &'a [Point],
+    nodes: Mutex<Vec<(Node, u32)>>,
+}
+
+impl<'a> Root<'a> {
+    fn init(attacks: &'a [Point], limit: u8) -> Self {
+        let init = Node::unit_dn(attacks.len() as u32, limit); // trick
+        Self {
+            attacks: attacks,
+            nodes: Mutex::new(vec![(init, 0); attacks.len()]),
+        }
+    }
+
+    // Takes the best attack counting virtual losses, or returns the root node if solved
+    fn take(&self) -> Result<(usize, Selection), Node> {
+        let mut nodes = self.nodes.lock().unwrap();
+        let mut best = 0;
+        let mut current = Node::zero_dn(0);
+        let mut next1 = Node::zero_dn(0);
+        let mut next2 = Node::zero_dn(0);
+        for (i, &(node, n_workers)) in nodes.iter().enumerate() {
+            current = current.min_pn_sum_dn(node);
+            let pn = if node.dn == 0 {
+                INF
+            } else {
+                node.pn.saturating_add(n_workers * VIRTUAL_LOSS)
+            };
+            let child = Node::new(pn, node.dn, node.limit);
+            if child.pn < next1.pn {
+                best = i;
+                next2 = next1;
+                next1 = child;
+            } else if child.pn < next2.pn {
+                next2 = child;
+            }
+        }
+        if current.pn == 0 || current.dn == 0 {
+            return Err(current);
+        }
+        nodes[best].1 += 1;
+        let selection = Selection {
+            best: Some(self.attacks[best]),
+            current: current,
+            next1: next1,
+            next2: next2,
+            n_others: 0,
+            cost: 0,
+        };
+        Ok((best, selection))
+    }
+
+    fn put(&self, i: usize, node: Node) {
+        let mut nodes = self.nodes.lock().unwrap();
+        nodes[i] = (node, nodes[i].1 - 1);
+    }
+}
+
+struct Worker<'a> {
+    attacker_table: Table,
+    defender_table: Table,
+    attacker_vcf_depth: u8,
+    defender_vcf_depth: u8,
+    attacker_vcf_solver: vcf::IDDFSSolver,
+    defender_vcf_solver: vcf::IDDFSSolver,
+    attacks_cache: LruCache<u64, Result<Vec<Point>, Node>>,
+    defences_cache: LruCache<u64, Result<Vec<Point>, Node>>,
+    stop: &'a AtomicBool,
+}
+
+impl<'a> Worker<'a> {
+    fn init(
+        attacker_vcf_depth: u8,
+        defender_vcf_depth: u8,
+        attacker_shared: SharedTable,
+        defender_shared: SharedTable,
+        bytes: Option<usize>,
+        stop: &'a AtomicBool,
+    ) -> Self {
+        // Bytes are divided as in DFPNSVCTSolver
+        let (attacker_table, defender_table, attacker_vcf_solver, defender_vcf_solver) = match bytes
+        {
+            Some(bytes) => (
+                Table::bounded_with_shared(bytes / 8 * 3, attacker_shared),
+                Table::bounded_with_shared(bytes / 8 * 3, defender_shared),
+                vcf::IDDFSSolver::init_bounded([1].to_vec(), bytes / 8),
+                vcf::IDDFSSolver::init_bounded([1].to_vec(), bytes / 8),
+            ),
+            None => (
+                Table::with_shared(attacker_shared),
+                Table::with_shared(defender_shared),
+                vcf::IDDFSSolver::init([1].to_vec()),
+                vcf::IDDFSSolver::init([1].to_vec()),
+            ),
+        };
+        Self {
+            attacker_table: attacker_table,
+            defender_table: defender_table,
+            attacker_vcf_depth: attacker_vcf_depth,
+            defender_vcf_depth: defender_vcf_depth,
+            attacker_vcf_solver: attacker_vcf_solver,
+            defender_vcf_solver: defender_vcf_solver,
+            attacks_cache: LruCache::new(1000),
+            defences_cache: LruCache::new(1000),
+            stop: stop,
+        }
+    }
+
+    fn stats(&self) -> Stats {
+        solver_stats(
+            [&self.attacker_table, &self.defender_table],
+            [&self.attacker_vcf_solver, &self.defender_vcf_solver],
+        )
+    }
+
+    fn search_root(&mut self, state: &mut VCTState, root: &Root) -> bool {
+        while !self.stop.load(Ordering::Relaxed) {
+            let (i, selection) = match root.take() {
+                Ok(taken) => taken,
+                // Solved by another worker, whose tables keep the proof
+                Err(_) => return false,
+            };
+            let next_threshold = Traverser::next_threshold_attack(self, &selection, Node::inf());
+            let node = state.into_play(selection.best, |child| {
+                let result = self.search_defences(child, next_threshold);
+                self.attacker_table().insert(child, result);
+                result
+            });
+            root.put(i, node);
+            if node.proven() {
+                self.stop.store(true, Ordering::Relaxed);
+                return true;
+            }
+        }
+        false
+    }
+}
+
+impl<'a> VCTSolver for Worker<'a> {}
+
+impl<'a> Searcher for Worker<'a> {}
+
+impl<'a> Generator for Worker<'a> {
+    fn attacks_cache(&mut self) -> &mut LruCache<u64, Result<Vec<Point>, Node>> {
+        &mut self.attacks_cache
+    }
+
+    fn defences_cache(&mut self) -> &mut LruCache<u64, Result<Vec<Point>, Node>> {
+        &mut self.defences_cache
+    }
+}
+
+impl<'a> VCFHelper for Worker<'a> {
+    fn attacker_vcf_depth(&self) -> u8 {
+        self.attacker_vcf_depth
+    }
+
+    fn defender_vcf_depth(&self) -> u8 {
+        self.defender_vcf_depth
+    }
+
+    fn attacker_vcf_solver(&mut self) -> &mut vcf::IDDFSSolver {
+        &mut self.attacker_vcf_solver
+    }
+
+    fn defender_vcf_solver(&mut self) -> &mut vcf::IDDFSSolver {
+        &mut self.defender_vcf_solver
+    }
+}
+
+impl<'a> Traverser for Worker<'a> {
+    fn next_threshold_attack(&self, selection: &Selection, threshold: Node) -> Node {
+        DFPNSTraverser::next_threshold_attack(self, selection, threshold)
+    }
+
+    fn next_threshold_defence(&self, selection: &Selection, threshold: Node) -> Node {
+        DFPNSTraverser::next_threshold_defence(self, selection, threshold)
+    }
+
+    fn backoff(&self, current: Node, threshold: Node) -> bool {
+        // Interrupted nodes are never solved, so they are not shared
+        self.stop.load(Ordering::Relaxed)
+            || current.pn >= threshold.pn
+            || current.dn >= threshold.dn
+    }
+}
+
+impl<'a> DFPNSTraverser for Worker<'a> {}
+
+impl<'a> Selector for Worker<'a> {}
+
+impl<'a> ProofTree for Worker<'a> {
+    fn attacker_table(&mut self) -> &mut Table {
+        &mut self.attacker_table
+    }
+
+    fn defender_table(&mut self) -> &mut Table {
+        &mut self.defender_table
+    }
+}
+
+impl<'a> Resolver for Worker<'a> {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::*;
+
+    #[test]
+    fn test_search_solved_root() -> Result<(), String> {
+        let board = "H10,H9,J9,H8,I7/I11,I9,G8,J8,H7";
+        let board = Board::from_str_with(board, Notation::Alphanumeric)?;
+        let mut state = VCTState::init(&board, Player::Black, 4);
+        let attacks = [Point(5, 9)];
+        let root = Root::init(&attacks, state.limit);
+        let (i, _) = root.take().map_err(|_| "Root is solved")?;
+        root.put(i, Node::zero_pn(state.limit));
+
+        let stop = AtomicBool::new(false);
+        let shared = || Arc::new(RwLock::new(HashTable::new()));
+        let mut worker = Worker::init(1, 2, shared(), shared(), None, &stop);
+        // The root was proven by another worker, so that this one has no proof to resolve
+        assert!(!worker.search_root(&mut state, &root));
+
+        Ok(())
+    }
+}
@@ -0,0 +1,194 @@
+use crate::board::Point;
+use crate::mate::game::*;
+use crate::mate::mate::Mate;
+use crate::mate::state::State;
+use crate::mate::table::Stats;
+use crate::mate::vcf;
+use crate::mate::vct::generator::Generator;
+use crate::mate::vct::helper::VCFHelper;
+use crate::mate::vct::proof::*;
+use crate::mate::vct::resolver::Resolver;
+use crate::mate::vct::searcher::Searcher;
+use crate::mate::vct::selector::*;
+use crate::mate::vct::solver::solver_stats;
+use crate::mate::vct::state::VCTState;
+use crate::mate::vct::traverser::*;
+use lru::LruCache;
+
+pub struct StepVCTSolver {
+    attacker_table: Table,
+    defender_table: Table,
+    attacker_vcf_depth: u8,
+    defender_vcf_depth: u8,
+    attacker_vcf_solver: vcf::IDDFSSolver,
+    defender_vcf_solver: vcf::IDDFSSolver,
+    attacks_cache: LruCache<u64, Result<Vec<Point>, Node>>,
+    defences_cache: LruCache<u64, Result<Vec<Point>, Node>>,
+    max_nodes: usize,
+}
+
+impl StepVCTSolver {
+    pub fn init(attacker_vcf_depth: u8, defender_vcf_depth: u8) -> Self {
+        Self {
+            attacker_table: Table::new(),
+            defender_table: Table::new(),
+            attacker_vcf_depth: attacker_vcf_depth,
+            defender_vcf_depth: defender_vcf_depth,
+            attacker_vcf_solver: vcf::IDDFSSolver::init([1].to_vec()),
+            defender_vcf_solver: vcf::IDDFSSolver::init([1].to_vec()),
+            attacks_cache: LruCache::new(1000),
+            defences_cache: LruCache::new(1000),
+            max_nodes: 0,
+        }
+    }
+
+    // Tables take 3/8 of the bytes each, and VCF deadends take 1/8 each
+    pub fn init_bounded(attacker_vcf_depth: u8, defender_vcf_depth: u8, bytes: usize) -> Self {
+        Self {
+            attacker_table: Table::bounded(bytes / 8 * 3),
+            defender_table: Table::bounded(bytes / 8 * 3),
+            attacker_vcf_depth: attacker_vcf_depth,
+            defender_vcf_depth: defender_vcf_depth,
+            attacker_vcf_solver: vcf::IDDFSSolver::init_bounded([1].to_vec(), bytes / 8),
+            defender_vcf_solver: vcf::IDDFSSolver::init_bounded([1].to_vec(), bytes / 8),
+            attacks_cache: LruCache::new(1000),
+            defences_cache: LruCache::new(1000),
+            max_nodes: 0,
+        }
+    }
+
+    pub fn stats(&self) -> Stats {
+        solver_stats(
+            [&self.attacker_table, &self.defender_table],
+            [&self.attacker_vcf_solver, &self.defender_vcf_solver],
+        )
+    }
+
+    // Counts stored nodes including evicted ones, so that a bounded table does not stall steps
+    pub fn n_nodes(&self) -> usize {
+        self.attacker_table.stats().inserts + self.defender_table.stats().inserts
+    }
+
+    // Search until the root is solved or max_nodes more nodes are stored.
+    // Interrupted nodes leave their current estimates in the tables, so the next step resumes from them.
+    pub fn step(&mut self, state: &mut VCTState, max_nodes: usize) -> Node {
+        if state.limit == 0 {
+            return Node::zero_dn(state.limit);
+        }
+        self.max_nodes = self.n_nodes().saturating_add(max_nodes);
+        self.search_attacks(state, Node::inf())
+    }
+
+    // Evicted proofs are searched again without the limit of steps
+    pub fn resolve_all(&mut self, state: &mut VCTState) -> Option<Mate> {
+        self.max_nodes = usize::MAX;
+        self.resolve(state)
+    }
+
+    pub fn best_line(&mut self, state: &mut VCTState) -> Vec<Point> {
+        self.best_attacks(state)
+    }
+
+    fn best_attacks(&mut self, state: &mut VCTState) -> Vec<Point> {
+        let attacks = match state.check_event() {
+            Some(Forced(attack)) => vec![attack],
+            Some(Defeated(_)) => return vec![],
+            None => match self.generate_attacks(state) {
+                Ok(attacks) => attacks,
+                Err(_) => return vec![],
+            },
+        };
+        let best = self.select_attack(state, &attacks).best;
+        if self.attacker_table().lookup_next(state, best).is_none() {
+            return vec![];
+        }
+        state.into_play(best, |s| {
+            let mut result = vec![best.unwrap()];
+            result.extend(self.best_defences(s));
+            result
+        })
+    }
+
+    fn best_defences(&mut self, state: &mut VCTState) -> Vec<Point> {
+        if state.limit <= 1 {
+            return vec![];
+        }
+        let defences = match state.check_event() {
+            Some(Forced(defence)) => vec![defence],
+            Some(Defeated(_)) => return vec![],
+            None => match self.generate_defences(state) {
+                Ok(defences) => defences,
+                Err(_) => return vec![],
+            },
+        };
+        let best = self.select_defence(state, &defences).best;
+        if self.defender_table().lookup_next(state, best).is_none() {
+            return vec![];
+        }
+        state.into_play(best, |s| {
+            let mut result = vec![best.unwrap()];
+            result.extend(self.best_attacks(s));
+            result
+        })
+    }
+}
+
+impl Searcher for StepVCTSolver {}
+
+impl Generator for StepVCTSolver {
+    fn attacks_cache(&mut self) -> &mut LruCache<u64, Result<Vec<Point>, Node>> {
+        &mut self.attacks_cache
+    }
+
+    fn defences_cache(&mut self) -> &mut LruCache<u64, Result<Vec<Point>, Node>> {
+        &mut self.defences_cache
+    }
+}
+
+impl VCFHelper for StepVCTSolver {
+    fn attacker_vcf_depth(&self) -> u8 {
+        self.attacker_vcf_depth
+    }
+
+    fn defender_vcf_depth(&self) -> u8 {
+        self.defender_vcf_depth
+    }
+
+    fn attacker_vcf_solver(&mut self) -> &mut vcf::IDDFSSolver {
+        &mut self.attacker_vcf_solver
+    }
+
+    fn defender_vcf_solver(&mut self) -> &mut vcf::IDDFSSolver {
+        &mut self.defender_vcf_solver
+    }
+}
+
+impl Traverser for StepVCTSolver {
+    fn next_threshold_attack(&self, selection: &Selection, threshold: Node) -> Node {
+        DFPNSTraverser::next_threshold_attack(self, selection, threshold)
+    }
+
+    fn next_threshold_defence(&self, selection: &Selection, threshold: Node) -> Node {
+        DFPNSTraverser::next_threshold_defence(self, selection, threshold)
+    }
+
+    fn backoff(&self, current: Node, threshold: Node) -> bool {
+        self.n_nodes() >= self.max_nodes || current.pn >= threshold.pn || current.dn >= threshold.dn
+    }
+}
+
+impl DFPNSTraverser for StepVCTSolver {}
+
+impl Selector for StepVCTSolver {}
+
+impl ProofTree for StepVCTSolver {
+    fn attacker_table(&mut self) -> &mut Table {
+        &mut self.attacker_table
+    }
+
+    fn defender_table(&mut self) -> &mut Table {
+        &mut self.defender_table
+    }
+}
+
+impl Resolver for StepVCTSolver {}
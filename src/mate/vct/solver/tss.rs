@@ -0,0 +1,200 @@
+use crate::board::Point;
+use crate::mate::mate::Mate;
+use crate::mate::state::State;
+use crate::mate::tss::ThreatSpace;
+use crate::mate::vcf;
+use crate::mate::vct::generator::Generator;
+use crate::mate::vct::helper::VCFHelper;
+use crate::mate::vct::proof::*;
+use crate::mate::vct::resolver::Resolver;
+use crate::mate::vct::searcher::Searcher;
+use crate::mate::vct::selector::*;
+use crate::mate::vct::solver::VCTSolver;
+use crate::mate::vct::state::VCTState;
+use crate::mate::vct::traverser::*;
+use lru::LruCache;
+
+const MAX_BRANCHES: usize = 10000;
+
+// Df-pn where attacks start from the next gain squares of winning sequences in the threat space of the root,
+// while defences are generated as usual. Threat-space search assumes the defender takes all cost squares at once
+// and ignores counter fours, so the other attacks by potentials follow, which the df-pn verifies.
+// This orders df-pn by the threat space of the root rather than searching the threat space of every node.
+// Only gain squares of the root sequences are added beyond the generator, so once the defender breaks them all,
+// a mate which needs a move of a new sequence with too low potentials is not found.
+pub struct TSSVCTSolver {
+    attacker_table: Table,
+    defender_table: Table,
+    attacker_vcf_depth: u8,
+    defender_vcf_depth: u8,
+    attacker_vcf_solver: vcf::IDDFSSolver,
+    defender_vcf_solver: vcf::IDDFSSolver,
+    attacks_cache: LruCache<u64, Result<Vec<Point>, Node>>,
+    defences_cache: LruCache<u64, Result<Vec<Point>, Node>>,
+    wins: Vec<Vec<Point>>,
+}
+
+impl TSSVCTSolver {
+    pub fn init(attacker_vcf_depth: u8, defender_vcf_depth: u8) -> Self {
+        Self {
+            attacker_table: Table::new(),
+            defender_table: Table::new(),
+            attacker_vcf_depth: attacker_vcf_depth,
+            defender_vcf_depth: defender_vcf_depth,
+            attacker_vcf_solver: vcf::IDDFSSolver::init([1].to_vec()),
+            defender_vcf_solver: vcf::IDDFSSolver::init([1].to_vec()),
+            attacks_cache: LruCache::new(1000),
+            defences_cache: LruCache::new(1000),
+            wins: vec![],
+        }
+    }
+
+    fn search_wins(state: &VCTState) -> Vec<Vec<Point>> {
+        let board = state.game().board();
+        let mut space = ThreatSpace::init(board, state.attacker, state.limit);
+        space.search(MAX_BRANCHES)
+    }
+
+    // The next gain square of each winning sequence, unless the defender took some of them
+    fn next_gains(wins: &[Vec<Point>], state: &VCTState) -> Vec<Point> {
+        let board = state.game().board();
+        let mut result = vec![];
+        for win in wins {
+            let next = win
+                .iter()
+                .find(|&&p| board.stone(p) != Some(state.attacker));
+            if let Some(&p) = next {
+                if board.stone(p).is_none() && !result.contains(&p) {
+                    result.push(p);
+                }
+            }
+        }
+        result
+    }
+}
+
+// The threat space is searched from the root itself, since the first expanded node may already follow a forced move
+impl VCTSolver for TSSVCTSolver {
+    fn solve(&mut self, state: &mut VCTState) -> Option<Mate> {
+        self.wins = Self::search_wins(state);
+        if self.search(state) {
+            self.resolve(state)
+        } else {
+            None
+        }
+    }
+
+    fn solve_defended(&mut self, state: &mut VCTState) -> Option<Mate> {
+        self.wins = Self::search_wins(state);
+        if self.search_defences(state, Node::inf()).proven() {
+            self.resolve_defences(state)
+        } else {
+            None
+        }
+    }
+}
+
+impl Searcher for TSSVCTSolver {}
+
+impl Generator for TSSVCTSolver {
+    fn attacks_cache(&mut self) -> &mut LruCache<u64, Result<Vec<Point>, Node>> {
+        &mut self.attacks_cache
+    }
+
+    fn defences_cache(&mut self) -> &mut LruCache<u64, Result<Vec<Point>, Node>> {
+        &mut self.defences_cache
+    }
+
+    fn compute_attacks(&mut self, state: &mut VCTState) -> Result<Vec<Point>, Node> {
+        if self.solve_attacker_vcf(state).is_some() {
+            return Err(Node::zero_pn(state.limit));
+        }
+
+        let maybe_threat = self.solve_defender_threat(state);
+        let maybe_threat_defences = maybe_threat.map(|t| state.threat_defences(&t));
+        let mut result = Self::next_gains(&self.wins, state);
+        for (p, _) in state.sorted_potentials(3, None) {
+            if !result.contains(&p) {
+                result.push(p);
+            }
+        }
+        result.retain(|&p| !state.is_forbidden_move(p));
+        if let Some(threat_defences) = maybe_threat_defences {
+            result.retain(|p| threat_defences.contains(p));
+        }
+
+        if result.is_empty() {
+            return Err(Node::zero_dn(state.limit));
+        }
+
+        Ok(result)
+    }
+}
+
+impl VCFHelper for TSSVCTSolver {
+    fn attacker_vcf_depth(&self) -> u8 {
+        self.attacker_vcf_depth
+    }
+
+    fn defender_vcf_depth(&self) -> u8 {
+        self.defender_vcf_depth
+    }
+
+    fn attacker_vcf_solver(&mut self) -> &mut vcf::IDDFSSolver {
+        &mut self.attacker_vcf_solver
+    }
+
+    fn defender_vcf_solver(&mut self) -> &mut vcf::IDDFSSolver {
+        &mut self.defender_vcf_solver
+    }
+}
+
+impl Traverser for TSSVCTSolver {
+    fn next_threshold_attack(&self, selection: &Selection, threshold: Node) -> Node {
+        DFPNSTraverser::next_threshold_attack(self, selection, threshold)
+    }
+
+    fn next_threshold_defence(&self, selection: &Selection, threshold: Node) -> Node {
+        DFPNSTraverser::next_threshold_defence(self, selection, threshold)
+    }
+}
+
+impl DFPNSTraverser for TSSVCTSolver {}
+
+impl Selector for TSSVCTSolver {}
+
+impl ProofTree for TSSVCTSolver {
+    fn attacker_table(&mut self) -> &mut Table {
+        &mut self.attacker_table
+    }
+
+    fn defender_table(&mut self) -> &mut Table {
+        &mut self.defender_table
+    }
+}
+
+impl Resolver for TSSVCTSolver {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::board::*;
+
+    #[test]
+    fn test_root_wins() -> Result<(), String> {
+        // White makes a four, so that black has to block it at J8 before any attack
+        let board = "G8,H8,G9,H10,I9,J3/J4,J5,J6,A1,A3,A5";
+        let board = Board::from_str_with(board, Notation::Alphanumeric)?;
+        let state = &mut VCTState::init_defending(&board, Player::Black, 4);
+        state.play(Some("J7".parse::<Point>()?));
+        let mut solver = TSSVCTSolver::init(4, 2);
+        solver.solve(state);
+
+        let board = state.game().board();
+        let root_wins = ThreatSpace::init(board, Player::Black, state.limit).search(MAX_BRANCHES);
+        assert!(!root_wins.is_empty());
+        assert_eq!(solver.wins, root_wins);
+
+        Ok(())
+    }
+}
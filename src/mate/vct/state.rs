@@ -6,6 +6,7 @@ use crate::mate::mate::Mate;
 use crate::mate::state::State;
 use crate::mate::vcf::VCFState;
 
+#[derive(Clone)]
 pub struct VCTState {
     game: Game,
     pub attacker: Player,
@@ -29,6 +30,18 @@ impl VCTState {
         Self::new(game, limit, field)
     }
 
+    // State of the defender to move, after which the attacker has the limit
+    pub fn init_defending(board: &Board, attacker: Player, limit: u8) -> Self {
+        let game = Game::init(board, attacker.opponent());
+        let field = PotentialField::init(attacker, 2, board);
+        Self {
+            game: game,
+            attacker: attacker,
+            limit: limit.saturating_add(1),
+            field: field,
+        }
+    }
+
     pub fn vcf_state(&self, max_limit: u8) -> VCFState {
         let game = self.game.clone();
         let limit = self.limit.min(max_limit);
@@ -55,12 +68,17 @@ impl VCTState {
         self.game().check_event()
     }
 
-    pub fn next_zobrist_hash(&mut self, next_move: Option<Point>) -> u64 {
+    // Tables are keyed by boards regardless of limits
+    pub fn key(&self) -> u64 {
+        self.game.board().zobrist_hash()
+    }
+
+    pub fn next_key(&mut self, next_move: Option<Point>) -> (u64, u8) {
         // Update only game in order not to cause updating state.field (which costs high)
         let limit = self.limit;
         let next_limit = if !self.attacking() { limit - 1 } else { limit };
-        self.game
-            .into_play(next_move, |g| g.zobrist_hash(next_limit))
+        let key = self.game.into_play(next_move, |g| g.board().zobrist_hash());
+        (key, next_limit)
     }
 
     pub fn sorted_potentials(&self, min: u8, only: Option<Vec<Point>>) -> Vec<(Point, u8)> {
@@ -75,6 +93,30 @@ impl VCTState {
         result
     }
 
+    pub fn potential(&self, p: Point) -> u8 {
+        self.field.get(p)
+    }
+
+    // Whether the player to move makes a four by the move
+    pub fn is_four_move(&self, p: Point) -> bool {
+        let turn = self.game().turn;
+        self.game()
+            .board()
+            .structures_on(p, turn, Sword)
+            .next()
+            .is_some()
+    }
+
+    // Whether the player to move makes a three by the move
+    pub fn is_three_move(&self, p: Point) -> bool {
+        let turn = self.game().turn;
+        self.game()
+            .board()
+            .structures_on(p, turn, Two)
+            .next()
+            .is_some()
+    }
+
     pub fn sort_by_potential(&self, points: Vec<Point>) -> Vec<(Point, u8)> {
         let mut result: Vec<_> = points.into_iter().map(|p| (p, self.field.get(p))).collect();
         result.sort_by(|&a, &b| b.1.cmp(&a.1));
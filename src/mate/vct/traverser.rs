@@ -2,7 +2,7 @@ mod dfpns;
 mod dfs;
 mod pns;
 
-pub use dfpns::DFPNSTraverser;
+pub use dfpns::{DFPNPTraverser, DFPNSTraverser, DFWPNSTraverser};
 pub use dfs::DFSTraverser;
 pub use pns::PNSTraverser;
 
@@ -26,17 +26,20 @@ pub trait Traverser: Selector {
     where
         F: Fn(&mut Self, &mut VCTState, Node) -> Node,
     {
-        loop {
+        self.attacker_table().enter();
+        let selection = loop {
             let selection = self.select_attack(state, &attacks);
             if self.backoff(selection.current, threshold) {
-                return selection;
+                break selection;
             }
             let next_threshold = self.next_threshold_attack(&selection, threshold);
             state.into_play(selection.best, |child| {
                 let result = search_defences(self, child, next_threshold);
                 self.attacker_table().insert(child, result);
             });
-        }
+        };
+        self.attacker_table().leave();
+        selection
     }
 
     fn traverse_defences<F>(
@@ -49,17 +52,20 @@ pub trait Traverser: Selector {
     where
         F: Fn(&mut Self, &mut VCTState, Node) -> Node,
     {
-        loop {
+        self.defender_table().enter();
+        let selection = loop {
             let selection = self.select_defence(state, &defences);
             if self.backoff(selection.current, threshold) {
-                return selection;
+                break selection;
             }
             let next_threshold = self.next_threshold_defence(&selection, threshold);
             state.into_play(selection.best, |child| {
                 let result = search_defences(self, child, next_threshold);
                 self.defender_table().insert(child, result);
             });
-        }
+        };
+        self.defender_table().leave();
+        selection
     }
 
     fn backoff(&self, current: Node, threshold: Node) -> bool {
@@ -22,3 +22,35 @@ pub trait DFPNSTraverser {
         Node::new(pn, dn, selection.next1.limit)
     }
 }
+
+// A weak proof number exceeds the threshold as soon as the max one exceeds it minus the other children
+pub trait DFWPNSTraverser {
+    fn next_threshold_attack(&self, selection: &Selection, threshold: Node) -> Node {
+        let pn = threshold.pn.min(selection.next2.pn.saturating_add(1));
+        let dn = threshold.dn.saturating_sub(selection.n_others);
+        Node::new(pn, dn, selection.next1.limit)
+    }
+
+    fn next_threshold_defence(&self, selection: &Selection, threshold: Node) -> Node {
+        let pn = threshold.pn.saturating_sub(selection.n_others);
+        let dn = threshold.dn.min(selection.next2.dn.saturating_add(1));
+        Node::new(pn, dn, selection.next1.limit)
+    }
+}
+
+// Edge costs are added to children in selection, so they are subtracted from the thresholds of them
+pub trait DFPNPTraverser {
+    fn next_threshold_attack(&self, selection: &Selection, threshold: Node) -> Node {
+        let pn = threshold.pn.min(selection.next2.pn.saturating_add(1));
+        let pn = pn.saturating_sub(selection.cost);
+        let dn = (threshold.dn - selection.current.dn).saturating_add(selection.next1.dn);
+        Node::new(pn, dn, selection.next1.limit)
+    }
+
+    fn next_threshold_defence(&self, selection: &Selection, threshold: Node) -> Node {
+        let pn = (threshold.pn - selection.current.pn).saturating_add(selection.next1.pn);
+        let dn = threshold.dn.min(selection.next2.dn.saturating_add(1));
+        let dn = dn.saturating_sub(selection.cost);
+        Node::new(pn, dn, selection.next1.limit)
+    }
+}
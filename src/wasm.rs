@@ -1,8 +1,20 @@
+mod field;
+mod position;
+mod solution;
+#[cfg(feature = "vct")]
+mod step;
+
 use super::board::*;
 use super::mate;
 use std::convert::{From, TryFrom};
 use wasm_bindgen::prelude::*;
 
+pub use field::{markers, potentials, Marker};
+pub use position::Position;
+pub use solution::{solve_detail, EndKind, Solution};
+#[cfg(feature = "vct")]
+pub use step::{StepSolver, StepStatus};
+
 #[wasm_bindgen]
 pub fn solve(
     mode: u8,
@@ -54,6 +66,52 @@ pub fn decode_y(code: u8) -> u8 {
     Point::try_from(code).unwrap().1
 }
 
+#[wasm_bindgen]
+pub fn parse_points(s: &str, notation: u8) -> Option<Box<[u8]>> {
+    let notation = Notation::try_from(notation).ok()?;
+    let points = Points::from_str_with(s, notation).ok()?;
+    Some(<Vec<u8>>::from(points).into_boxed_slice())
+}
+
+#[wasm_bindgen]
+pub fn format_points(codes: &[u8], notation: u8) -> Option<String> {
+    let notation = Notation::try_from(notation).ok()?;
+    let points = Points::try_from(codes).ok()?;
+    Some(points.to_string_with(notation))
+}
+
+#[wasm_bindgen]
+pub fn solve_str(
+    mode: u8,
+    limit: u8,
+    board: &str,
+    black: bool,
+    threat_limit: u8,
+    notation: u8,
+) -> Option<String> {
+    let mode = mate::SolveMode::try_from(mode).ok()?;
+    let notation = Notation::try_from(notation).ok()?;
+    let board = Board::from_str_with(board, notation).ok()?;
+    let player = Player::from(black);
+    let solution = mate::solve(mode, limit, &board, player, threat_limit);
+    solution.map(|s| Points(s.path).to_string_with(notation))
+}
+
+#[wasm_bindgen]
+pub fn forbiddens_str(board: &str, notation: u8) -> Option<String> {
+    let notation = Notation::try_from(notation).ok()?;
+    let board = Board::from_str_with(board, notation).ok()?;
+    let points = board.forbiddens().into_iter().map(|(_, p)| p).collect();
+    Some(Points(points).to_string_with(notation))
+}
+
+#[wasm_bindgen]
+pub fn pretty_str(board: &str, notation: u8) -> Option<String> {
+    let notation = Notation::try_from(notation).ok()?;
+    let board = Board::from_str_with(board, notation).ok()?;
+    Some(board.to_pretty_string())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -72,4 +130,38 @@ mod tests {
     fn test_decode_y() {
         assert_eq!(decode_y(65), 5);
     }
+
+    #[test]
+    fn test_parse_points() {
+        let result = parse_points("5,6 8,8", 10).unwrap();
+        assert_eq!(*result, [65, 112]);
+        assert!(parse_points("5,6", 99).is_none());
+    }
+
+    #[test]
+    fn test_format_points() {
+        let result = format_points(&[65, 112], 1).unwrap();
+        assert_eq!(result, "e6h8");
+    }
+
+    #[test]
+    #[cfg(feature = "vct")]
+    fn test_solve_str() {
+        let board = "H10,H9,J9,H8,I7/I11,I9,G8,J8,H7";
+        let result = solve_str(16, 4, board, true, 1, 0);
+        assert_eq!(result, Some("F10,G9,I10,G10,H11,H12,G12".to_string()));
+
+        let moves = "h8i9h9i11h10g8j9j8i7h7";
+        let result = solve_str(16, 4, moves, true, 1, 1);
+        assert_eq!(result, Some("f10g9i10g10h11h12g12".to_string()));
+
+        assert_eq!(solve_str(16, 3, board, true, 1, 0), None);
+        assert_eq!(solve_str(16, 4, "Z99", true, 1, 0), None);
+    }
+
+    #[test]
+    fn test_forbiddens_str() {
+        let result = forbiddens_str("H9,G8,I8,H7/", 0);
+        assert_eq!(result, Some("H8".to_string()));
+    }
 }
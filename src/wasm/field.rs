@@ -0,0 +1,104 @@
+use crate::analysis::field::PotentialField;
+use crate::board::StructureKind::*;
+use crate::board::*;
+use std::convert::{From, TryFrom};
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Marker {
+    Five = 1,
+    Four = 2,
+    OpenFour = 4,
+    Three = 8,
+    Forbidden = 16,
+    FourStone = 32,
+    ThreeStone = 64,
+}
+
+#[wasm_bindgen]
+pub fn potentials(blacks: &[u8], whites: &[u8], black: bool, min: u8) -> Option<Box<[u8]>> {
+    let board = decode_board(blacks, whites)?;
+    Some(potentials_of(&board, Player::from(black), min))
+}
+
+#[wasm_bindgen]
+pub fn markers(blacks: &[u8], whites: &[u8], black: bool) -> Option<Box<[u8]>> {
+    let board = decode_board(blacks, whites)?;
+    Some(markers_of(&board, Player::from(black)))
+}
+
+pub fn potentials_of(board: &Board, r: Player, min: u8) -> Box<[u8]> {
+    PotentialField::init(r, min, board)
+        .values()
+        .into_boxed_slice()
+}
+
+// Bit flags of Marker for each point ordered by point code
+pub fn markers_of(board: &Board, r: Player) -> Box<[u8]> {
+    let mut result = [0u8; (RANGE as usize) * (RANGE as usize)];
+    let mut mark = |p: Point, m: Marker| result[u8::from(p) as usize] |= m as u8;
+    for (kind, marker) in [
+        (Four, Marker::Five),
+        (Sword, Marker::Four),
+        (Three, Marker::OpenFour),
+        (Two, Marker::Three),
+    ] {
+        for p in board.structures(r, kind).flat_map(|s| s.eyes()) {
+            mark(p, marker);
+        }
+    }
+    if r.is_black() {
+        for (_, p) in board.forbiddens() {
+            mark(p, Marker::Forbidden);
+        }
+    }
+    for t in board.threats(r) {
+        let marker = if t.kind.is_four() {
+            Marker::FourStone
+        } else if t.real {
+            Marker::ThreeStone
+        } else {
+            continue;
+        };
+        for &p in &t.stones {
+            mark(p, marker);
+        }
+    }
+    Box::new(result)
+}
+
+fn decode_board(blacks: &[u8], whites: &[u8]) -> Option<Board> {
+    let blacks = Points::try_from(blacks).ok()?;
+    let whites = Points::try_from(whites).ok()?;
+    Some(Board::from_stones(&blacks, &whites))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_markers() -> Result<(), String> {
+        let blacks = <Vec<u8>>::from("H8,I8,J8,G9,G10".parse::<Points>()?);
+
+        let result = markers(&blacks, &[], true).unwrap();
+        assert_eq!(result.len(), 225);
+        let at = |s: &str| s.parse::<Point>().map(|p| result[u8::from(p) as usize]);
+        assert_eq!(at("K8")?, Marker::OpenFour as u8 | Marker::Four as u8);
+        assert_eq!(at("H8")?, Marker::ThreeStone as u8);
+        assert_eq!(
+            at("G8")?,
+            Marker::OpenFour as u8 | Marker::Four as u8 | Marker::Three as u8
+        );
+        assert_eq!(at("A1")?, 0);
+
+        let result = potentials(&blacks, &[], true, 2).unwrap();
+        assert_eq!(result.len(), 225);
+        assert!(result[u8::from(Point(6, 7)) as usize] > 0);
+
+        assert!(markers(&[225], &[], true).is_none());
+
+        Ok(())
+    }
+}
@@ -0,0 +1,200 @@
+use super::field::{markers_of, potentials_of};
+use super::solution::{try_solve, Solution};
+use crate::board::*;
+use crate::mate;
+use std::convert::{From, TryFrom};
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+pub struct Position {
+    board: Board,
+    history: Vec<(Point, Option<Player>)>,
+}
+
+#[wasm_bindgen]
+impl Position {
+    #[wasm_bindgen(constructor)]
+    pub fn new() -> Self {
+        Self {
+            board: Board::new(),
+            history: vec![],
+        }
+    }
+
+    pub fn from_stones(blacks: &[u8], whites: &[u8]) -> Option<Position> {
+        let blacks = Points::try_from(blacks).ok()?;
+        let whites = Points::try_from(whites).ok()?;
+        Some(Self {
+            board: Board::from_stones(&blacks, &whites),
+            history: vec![],
+        })
+    }
+
+    pub fn put(&mut self, black: bool, code: u8) -> bool {
+        let p = match Point::try_from(code) {
+            Ok(p) => p,
+            Err(_) => return false,
+        };
+        self.history.push((p, self.board.stone(p)));
+        self.board.put_mut(Player::from(black), p);
+        true
+    }
+
+    pub fn remove(&mut self, code: u8) -> bool {
+        let p = match Point::try_from(code) {
+            Ok(p) => p,
+            Err(_) => return false,
+        };
+        self.history.push((p, self.board.stone(p)));
+        self.board.remove_mut(p);
+        true
+    }
+
+    pub fn undo(&mut self) -> bool {
+        match self.history.pop() {
+            Some((p, Some(r))) => self.board.put_mut(r, p),
+            Some((p, None)) => self.board.remove_mut(p),
+            None => return false,
+        }
+        true
+    }
+
+    pub fn stone(&self, code: u8) -> Option<bool> {
+        let p = Point::try_from(code).ok()?;
+        self.board.stone(p).map(bool::from)
+    }
+
+    pub fn blacks(&self) -> Box<[u8]> {
+        encode(self.board.stones(Player::Black))
+    }
+
+    pub fn whites(&self) -> Box<[u8]> {
+        encode(self.board.stones(Player::White))
+    }
+
+    pub fn forbiddens(&self) -> Box<[u8]> {
+        encode(self.board.forbiddens().into_iter().map(|(_, p)| p))
+    }
+
+    pub fn forbidden(&self, code: u8) -> u8 {
+        Point::try_from(code)
+            .ok()
+            .and_then(|p| self.board.forbidden_strict(p))
+            .map_or(0, u8::from)
+    }
+
+    pub fn structure_stones(&self, black: bool, kind: u8) -> Option<Box<[u8]>> {
+        let kind = StructureKind::try_from(kind).ok()?;
+        let r = Player::from(black);
+        let stones = self
+            .board
+            .structures(r, kind)
+            .flat_map(|s| s.stones())
+            .filter(|&p| self.board.stone(p) == Some(r));
+        Some(encode(stones))
+    }
+
+    pub fn structure_eyes(&self, black: bool, kind: u8) -> Option<Box<[u8]>> {
+        let kind = StructureKind::try_from(kind).ok()?;
+        let eyes = self
+            .board
+            .structures(Player::from(black), kind)
+            .flat_map(|s| s.eyes());
+        Some(encode(eyes))
+    }
+
+    pub fn potentials(&self, black: bool, min: u8) -> Box<[u8]> {
+        potentials_of(&self.board, Player::from(black), min)
+    }
+
+    pub fn markers(&self, black: bool) -> Box<[u8]> {
+        markers_of(&self.board, Player::from(black))
+    }
+
+    pub fn zobrist_hash(&self) -> u64 {
+        self.board.zobrist_hash()
+    }
+
+    pub fn to_pretty_string(&self) -> String {
+        self.board.to_pretty_string()
+    }
+
+    pub fn solve(&self, mode: u8, limit: u8, black: bool, threat_limit: u8) -> Option<Box<[u8]>> {
+        let mode = mate::SolveMode::try_from(mode).ok()?;
+        let player = Player::from(black);
+        let solution = mate::solve(mode, limit, &self.board, player, threat_limit);
+        solution.map(|s| <Vec<u8>>::from(Points(s.path)).into_boxed_slice())
+    }
+
+    pub fn solve_detail(&self, mode: u8, limit: u8, black: bool, threat_limit: u8) -> Solution {
+        Solution::new(try_solve(mode, limit, &self.board, black, threat_limit))
+    }
+}
+
+impl Default for Position {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+fn encode(points: impl Iterator<Item = Point>) -> Box<[u8]> {
+    let mut codes: Vec<u8> = points.map(u8::from).collect();
+    codes.sort_unstable();
+    codes.dedup();
+    codes.into_boxed_slice()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_put_and_undo() {
+        let mut position = Position::new();
+        let empty = position.zobrist_hash();
+
+        assert!(position.put(true, 112));
+        assert!(position.put(false, 113));
+        assert_eq!(position.stone(112), Some(true));
+        assert_eq!(position.stone(113), Some(false));
+        assert_eq!(*position.blacks(), [112]);
+
+        assert!(position.remove(112));
+        assert_eq!(position.stone(112), None);
+
+        assert!(position.undo());
+        assert_eq!(position.stone(112), Some(true));
+        assert!(position.undo());
+        assert!(position.undo());
+        assert!(!position.undo());
+        assert_eq!(position.zobrist_hash(), empty);
+
+        assert!(!position.put(true, 225));
+    }
+
+    #[test]
+    fn test_forbiddens() {
+        let blacks = [
+            u8::from(Point(7, 8)),
+            u8::from(Point(6, 7)),
+            u8::from(Point(8, 7)),
+            u8::from(Point(7, 6)),
+        ];
+        let mut position = Position::from_stones(&blacks, &[]).unwrap();
+        position.remove(u8::from(Point(7, 6)));
+        position.put(true, u8::from(Point(7, 5)));
+        position.undo();
+        position.undo();
+
+        let center = u8::from(Point(7, 7));
+        assert_eq!(*position.forbiddens(), [center]);
+        assert_eq!(
+            position.forbidden(center),
+            u8::from(ForbiddenKind::DoubleThree)
+        );
+
+        let eyes = position.structure_eyes(true, 2).unwrap();
+        assert!(eyes.contains(&center));
+        assert!(position.structure_eyes(true, 99).is_none());
+    }
+}
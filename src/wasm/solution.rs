@@ -0,0 +1,144 @@
+use crate::board::*;
+use crate::mate;
+use crate::mate::End;
+use std::convert::{From, TryFrom};
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum EndKind {
+    Fours,
+    Forbidden,
+    Unknown,
+}
+
+#[wasm_bindgen]
+pub struct Solution {
+    mate: Option<mate::Mate>,
+    error: Option<String>,
+}
+
+impl Solution {
+    pub fn new(result: Result<Option<mate::Mate>, &str>) -> Self {
+        match result {
+            Ok(mate) => Self {
+                mate: mate,
+                error: None,
+            },
+            Err(e) => Self {
+                mate: None,
+                error: Some(e.to_string()),
+            },
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl Solution {
+    #[wasm_bindgen(getter)]
+    pub fn found(&self) -> bool {
+        self.mate.is_some()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn end_kind(&self) -> Option<EndKind> {
+        self.mate.as_ref().map(|m| match m.end {
+            End::Fours(_, _) => EndKind::Fours,
+            End::Forbidden(_) => EndKind::Forbidden,
+            End::Unknown => EndKind::Unknown,
+        })
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn end_points(&self) -> Box<[u8]> {
+        let points = match self.mate.as_ref().map(|m| &m.end) {
+            Some(End::Fours(p1, p2)) => vec![*p1, *p2],
+            Some(End::Forbidden(p)) => vec![*p],
+            _ => vec![],
+        };
+        <Vec<u8>>::from(Points(points)).into_boxed_slice()
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn path(&self) -> Option<Box<[u8]>> {
+        self.mate
+            .as_ref()
+            .map(|m| <Vec<u8>>::from(Points(m.path.clone())).into_boxed_slice())
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn n_times(&self) -> u8 {
+        self.mate.as_ref().map_or(0, |m| m.n_times())
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn n_moves(&self) -> u8 {
+        self.mate.as_ref().map_or(0, |m| m.n_moves())
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn error(&self) -> Option<String> {
+        self.error.clone()
+    }
+}
+
+#[wasm_bindgen]
+pub fn solve_detail(
+    mode: u8,
+    limit: u8,
+    blacks: &[u8],
+    whites: &[u8],
+    black: bool,
+    threat_limit: u8,
+) -> Solution {
+    let result = Points::try_from(blacks).and_then(|blacks| {
+        let whites = Points::try_from(whites)?;
+        let board = Board::from_stones(&blacks, &whites);
+        try_solve(mode, limit, &board, black, threat_limit)
+    });
+    Solution::new(result)
+}
+
+pub fn try_solve(
+    mode: u8,
+    limit: u8,
+    board: &Board,
+    black: bool,
+    threat_limit: u8,
+) -> Result<Option<mate::Mate>, &'static str> {
+    let mode = mate::SolveMode::try_from(mode)?;
+    let player = Player::from(black);
+    mate::try_solve(mode, limit, board, player, threat_limit)
+}
+
+#[cfg(all(test, feature = "vct"))]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_solve_detail() -> Result<(), String> {
+        let blacks = <Vec<u8>>::from("H10,H9,J9,H8,I7".parse::<Points>()?);
+        let whites = <Vec<u8>>::from("I11,I9,G8,J8,H7".parse::<Points>()?);
+
+        let result = solve_detail(16, 4, &blacks, &whites, true, 1);
+        assert!(result.found());
+        assert_eq!(result.end_kind(), Some(EndKind::Fours));
+        assert_eq!(result.end_points().len(), 2);
+        assert_eq!(result.n_times(), 4);
+        assert_eq!(result.n_moves(), 7);
+        let path = Points::try_from(&*result.path().unwrap())?;
+        assert_eq!(path.to_string(), "F10,G9,I10,G10,H11,H12,G12");
+        assert_eq!(result.error(), None);
+
+        let result = solve_detail(16, 3, &blacks, &whites, true, 1);
+        assert!(!result.found());
+        assert_eq!(result.end_kind(), None);
+        assert_eq!(result.error(), None);
+
+        let result = solve_detail(99, 4, &blacks, &whites, true, 1);
+        assert!(!result.found());
+        assert_eq!(result.error(), Some("Unknown solve mode".to_string()));
+
+        Ok(())
+    }
+}
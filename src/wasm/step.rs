@@ -0,0 +1,120 @@
+use crate::board::*;
+use crate::mate;
+use std::convert::{From, TryFrom};
+use wasm_bindgen::prelude::*;
+
+#[wasm_bindgen]
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum StepStatus {
+    Running,
+    Found,
+    NotFound,
+}
+
+#[wasm_bindgen]
+pub struct StepSolver {
+    solver: mate::StepSolver,
+    solution: Option<mate::Mate>,
+}
+
+#[wasm_bindgen]
+impl StepSolver {
+    #[wasm_bindgen(constructor)]
+    pub fn new(
+        blacks: &[u8],
+        whites: &[u8],
+        black: bool,
+        limit: u8,
+        threat_limit: u8,
+    ) -> Result<StepSolver, String> {
+        let blacks = Points::try_from(blacks)?;
+        let whites = Points::try_from(whites)?;
+        let board = Board::from_stones(&blacks, &whites);
+        let player = Player::from(black);
+        Ok(Self {
+            solver: mate::StepSolver::init(&board, player, limit, threat_limit),
+            solution: None,
+        })
+    }
+
+    // Keeps search tables within table_bytes so that long searches do not exhaust memory
+    pub fn bounded(
+        blacks: &[u8],
+        whites: &[u8],
+        black: bool,
+        limit: u8,
+        threat_limit: u8,
+        table_bytes: usize,
+    ) -> Result<StepSolver, String> {
+        let blacks = Points::try_from(blacks)?;
+        let whites = Points::try_from(whites)?;
+        let board = Board::from_stones(&blacks, &whites);
+        let player = Player::from(black);
+        let solver =
+            mate::StepSolver::init_bounded(&board, player, limit, threat_limit, table_bytes);
+        Ok(Self {
+            solver: solver,
+            solution: None,
+        })
+    }
+
+    pub fn step(&mut self, max_nodes: usize) -> StepStatus {
+        match self.solver.step(max_nodes) {
+            mate::Step::Running => StepStatus::Running,
+            mate::Step::Found(m) => {
+                self.solution.replace(m);
+                StepStatus::Found
+            }
+            mate::Step::NotFound => StepStatus::NotFound,
+        }
+    }
+
+    pub fn solution(&self) -> Option<Box<[u8]>> {
+        self.solution
+            .as_ref()
+            .map(|s| <Vec<u8>>::from(Points(s.path.clone())).into_boxed_slice())
+    }
+
+    pub fn best_line(&mut self) -> Box<[u8]> {
+        <Vec<u8>>::from(Points(self.solver.best_line())).into_boxed_slice()
+    }
+
+    pub fn n_nodes(&self) -> usize {
+        self.solver.n_nodes()
+    }
+
+    pub fn evictions(&self) -> usize {
+        self.solver.stats().evictions
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_step_solver() -> Result<(), String> {
+        let blacks = "H10,H9,J9,H8,I7".parse::<Points>()?;
+        let whites = "I11,I9,G8,J8,H7".parse::<Points>()?;
+        let blacks = <Vec<u8>>::from(blacks);
+        let whites = <Vec<u8>>::from(whites);
+
+        let mut solver = StepSolver::new(&blacks, &whites, true, 4, 1)?;
+        let status = loop {
+            match solver.step(10) {
+                StepStatus::Running => continue,
+                status => break status,
+            }
+        };
+        assert_eq!(status, StepStatus::Found);
+        assert!(solver.n_nodes() > 0);
+
+        let solution = Points::try_from(&*solver.solution().unwrap())?;
+        assert_eq!(solution.to_string(), "F10,G9,I10,G10,H11,H12,G12");
+        assert_eq!(*solver.best_line(), *solver.solution().unwrap());
+
+        assert!(StepSolver::new(&[225], &[], true, 4, 1).is_err());
+
+        Ok(())
+    }
+}